@@ -0,0 +1,51 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use sortby::sort_small_by;
+
+const BATCH: usize = 1024;
+
+/// Distance and entity ID pairs, like the neighbours of an entity.
+fn batches<const N: usize>() -> Vec<[(f32, u32); N]> {
+    let mut rng = SmallRng::seed_from_u64(N as u64);
+    (0..BATCH)
+        .map(|_| {
+            let mut items = [(0.0, 0); N];
+            for item in items.iter_mut() {
+                *item = (rng.random_range(0.0..1000.0), rng.random());
+            }
+            items
+        })
+        .collect()
+}
+
+fn bench<const N: usize>(c: &mut Criterion) {
+    let input = batches::<N>();
+    let mut group = c.benchmark_group(format!("sort_small/{}", N));
+    group.bench_function("sort_unstable_by", |b| {
+        b.iter_batched_ref(
+            || input.clone(),
+            |batches| {
+                for items in batches.iter_mut() {
+                    items.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("sort_small_by", |b| {
+        b.iter_batched_ref(
+            || input.clone(),
+            |batches| {
+                for items in batches.iter_mut() {
+                    sort_small_by(items, |v| v.0);
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench::<4>, bench::<6>, bench::<8>);
+criterion_main!(benches);
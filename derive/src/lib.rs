@@ -0,0 +1,257 @@
+//! Derive macros for [`sortby`](https://docs.rs/sortby). Use them through the
+//! `derive` feature of `sortby` instead of depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Fields, Ident, Index, LitInt, LitStr, Meta,
+};
+
+/// One field taking part in the default sort.
+struct Level {
+    member: TokenStream2,
+    by: Option<Ident>,
+    desc: bool,
+    level: Option<u32>,
+}
+
+/// The declared rank of one variant in one order.
+struct Rank {
+    /// `None` for the default order.
+    name: Option<LitStr>,
+    rank: usize,
+}
+
+/// Derives `sortby::DefaultSort` from `#[sort(...)]` field attributes, see
+/// the trait's documentation.
+#[proc_macro_derive(DefaultSort, attributes(sort))]
+pub fn derive_default_sort(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "DefaultSort can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut levels = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = match field.ident {
+            Some(ref ident) => ident.to_token_stream(),
+            None => Index::from(index).to_token_stream(),
+        };
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("sort"))
+        {
+            levels.push(parse_level(member.clone(), &attr.meta)?);
+        }
+    }
+    if levels.is_empty() {
+        let span = match fields {
+            Fields::Named(fields) => fields.span(),
+            Fields::Unnamed(fields) => fields.span(),
+            Fields::Unit => input.ident.span(),
+        };
+        return Err(syn::Error::new(
+            span,
+            "DefaultSort needs at least one field marked with #[sort]",
+        ));
+    }
+    // a stable sort, so levels without an explicit `level` keep the field order
+    levels.sort_by_key(|level| level.level.unwrap_or(u32::MAX));
+
+    let compares = levels.iter().map(|level| {
+        let member = &level.member;
+        let (a, b) = match level.by {
+            Some(ref by) => (quote!(a.#member.#by()), quote!(b.#member.#by())),
+            None => (quote!(a.#member), quote!(b.#member)),
+        };
+        let (a, b) = if level.desc { (b, a) } else { (a, b) };
+        quote! {
+            .then_with(|| {
+                ::core::cmp::PartialOrd::partial_cmp(&#a, &#b)
+                    .unwrap_or(::core::cmp::Ordering::Equal)
+            })
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::sortby::DefaultSort for #name #ty_generics #where_clause {
+            fn default_sort(a: &Self, b: &Self) -> ::core::cmp::Ordering {
+                ::core::cmp::Ordering::Equal #(#compares)*
+            }
+        }
+    })
+}
+
+fn parse_level(member: TokenStream2, meta: &Meta) -> syn::Result<Level> {
+    let mut level = Level {
+        member,
+        by: None,
+        desc: false,
+        level: None,
+    };
+    match meta {
+        Meta::Path(_) => {}
+        Meta::List(list) => list.parse_nested_meta(|meta| {
+            if meta.path.is_ident("desc") {
+                level.desc = true;
+            } else if meta.path.is_ident("by") {
+                level.by = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+            } else if meta.path.is_ident("level") {
+                level.level = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else {
+                return Err(meta.error("expected `desc`, `by = \"method\"` or `level = N`"));
+            }
+            Ok(())
+        })?,
+        Meta::NameValue(meta) => {
+            return Err(syn::Error::new(
+                meta.span(),
+                "expected #[sort] or #[sort(...)]",
+            ))
+        }
+    }
+    Ok(level)
+}
+
+/// Derives `sortby::VariantOrder` from `#[order(...)]` variant attributes,
+/// see the trait's documentation.
+#[proc_macro_derive(EnumOrder, attributes(order))]
+pub fn derive_enum_order(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_enum_order(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_enum_order(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let variants = match input.data {
+        Data::Enum(ref data) => &data.variants,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "EnumOrder can only be derived for enums",
+            ))
+        }
+    };
+
+    // the ranks of every variant, in declaration order
+    let mut ranks = Vec::new();
+    // the named orders, in the order they are first used
+    let mut names: Vec<LitStr> = Vec::new();
+    for variant in variants {
+        let variant_ranks = parse_ranks(&variant.attrs)?;
+        for rank in &variant_ranks {
+            if let Some(ref name) = rank.name {
+                if names.iter().all(|known| known.value() != name.value()) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        ranks.push((&variant.ident, variant_ranks));
+    }
+    if ranks.iter().all(|(_, ranks)| ranks.is_empty()) {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "EnumOrder needs at least one variant marked with #[order]",
+        ));
+    }
+
+    let rank_match = |name: Option<&str>| {
+        let arms = ranks.iter().map(|(ident, ranks)| {
+            // the target's `usize::MAX`, which may differ from the host's
+            let rank = ranks
+                .iter()
+                .find(|rank| rank.name.as_ref().map(LitStr::value).as_deref() == name)
+                .map_or(quote!(::core::primitive::usize::MAX), |rank| {
+                    rank.rank.to_token_stream()
+                });
+            quote!(Self::#ident { .. } => #rank,)
+        });
+        quote! {
+            match *value {
+                #(#arms)*
+            }
+        }
+    };
+    let default_ranks = rank_match(None);
+    let named_ranks = names.iter().map(|name| {
+        let ranks = rank_match(Some(&name.value()));
+        quote!(#name => ::core::option::Option::Some(|value: &Self| #ranks),)
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::sortby::VariantOrder for #name #ty_generics #where_clause {
+            fn variant_rank(value: &Self) -> usize {
+                #default_ranks
+            }
+
+            fn named_variant_ranks(
+                name: &str,
+            ) -> ::core::option::Option<fn(&Self) -> usize> {
+                match name {
+                    #(#named_ranks)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+fn parse_ranks(attrs: &[Attribute]) -> syn::Result<Vec<Rank>> {
+    let mut ranks: Vec<Rank> = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("order")) {
+        let list = match attr.meta {
+            Meta::List(ref list) => list,
+            _ => {
+                return Err(syn::Error::new(
+                    attr.meta.span(),
+                    "expected #[order(rank = N)] or #[order(name = \"...\", rank = N)]",
+                ))
+            }
+        };
+        let mut name = None;
+        let mut rank = None;
+        list.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("rank") {
+                rank = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else {
+                return Err(meta.error("expected `rank = N` or `name = \"...\"`"));
+            }
+            Ok(())
+        })?;
+        let rank = rank.ok_or_else(|| syn::Error::new(list.span(), "missing `rank = N`"))?;
+        let key = name.as_ref().map(LitStr::value);
+        if ranks
+            .iter()
+            .any(|known| known.name.as_ref().map(LitStr::value) == key)
+        {
+            return Err(syn::Error::new(
+                list.span(),
+                "the variant already has a rank in this order",
+            ));
+        }
+        ranks.push(Rank { name, rank });
+    }
+    Ok(ranks)
+}
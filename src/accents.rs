@@ -0,0 +1,116 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+/// Strips diacritics from Latin letters, e.g. to sort "émile" next to
+/// "Emile" without full collation: `sort_by(|c| fold_accents(&c.name))`.
+///
+/// Covers the Latin-1 Supplement and Latin Extended-A blocks, and drops
+/// combining marks, so decomposed input folds the same way. Ligatures and
+/// letters like `ß` expand to their usual ASCII spelling. Everything else
+/// is kept, and the input is borrowed if nothing changes.
+///
+/// ```
+/// use sortby::*;
+///
+/// let names = vec!["Emma", "émile", "Eric", "Zoë", "Emile"];
+/// let sorted: Vec<_> = names
+///     .into_iter()
+///     .sort_by(|v| fold_accents(v).to_lowercase())
+///     .collect();
+/// assert_eq!(sorted, vec!["émile", "Emile", "Emma", "Eric", "Zoë"]);
+/// ```
+pub fn fold_accents(s: &str) -> Cow<'_, str> {
+    if s.is_ascii() {
+        return Cow::Borrowed(s);
+    }
+    let mut folded = String::with_capacity(s.len());
+    let mut changed = false;
+    for c in s.chars() {
+        if is_combining_mark(c) {
+            changed = true;
+        } else if let Some(base) = fold(c) {
+            folded.push_str(base);
+            changed = true;
+        } else {
+            folded.push(c);
+        }
+    }
+    if changed {
+        Cow::Owned(folded)
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+fn is_combining_mark(c: char) -> bool {
+    ('\u{300}'..='\u{36f}').contains(&c)
+}
+
+fn fold(c: char) -> Option<&'static str> {
+    let base = match c {
+        'À'..='Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'à'..='å' | 'ā' | 'ă' | 'ą' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'ð' | 'ď' | 'đ' => "d",
+        'È'..='Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'Ĥ' | 'Ħ' => "H",
+        'ĥ' | 'ħ' => "h",
+        'Ì'..='Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'Ĳ' => "IJ",
+        'ĳ' => "ij",
+        'Ĵ' => "J",
+        'ĵ' => "j",
+        'Ķ' => "K",
+        'ķ' | 'ĸ' => "k",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "L",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' | 'Ŋ' => "N",
+        'ñ' | 'ń' | 'ņ' | 'ň' | 'ŉ' | 'ŋ' => "n",
+        'Ò'..='Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => "O",
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' => "S",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ſ' => "s",
+        'ß' => "ss",
+        'Ţ' | 'Ť' | 'Ŧ' => "T",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'Þ' => "TH",
+        'þ' => "th",
+        'Ù'..='Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'Ŵ' => "W",
+        'ŵ' => "w",
+        'Ý' | 'Ŷ' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_latin_diacritics() {
+        assert_eq!(fold_accents("Ærøskøbing"), "AEroskobing");
+        assert_eq!(fold_accents("Łódź, Straße"), "Lodz, Strasse");
+        assert_eq!(fold_accents("e\u{301}mile"), "emile");
+        assert_eq!(fold_accents("Zoë × Ελλάδα"), "Zoe × Ελλάδα");
+        assert!(matches!(fold_accents("plain"), Cow::Borrowed(_)));
+        assert!(matches!(fold_accents("日本"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,116 @@
+use core::cmp::Ordering;
+
+use crate::{Compare, SortError};
+
+/// Collects exactly `N` items into an array and sorts them in place, without
+/// touching the heap. Equal items keep their input order, or the reverse if
+/// `newest_first` is set.
+///
+/// Uses a binary insertion sort, which is stable and needs no scratch space
+/// but moves O(N²) items, so it is meant for the small `N` of fixed buffers.
+pub(crate) fn sort_into_array<I, C, const N: usize>(
+    mut iter: I,
+    compare: &C,
+    newest_first: bool,
+) -> Result<[I::Item; N], SortError>
+where
+    I: Iterator,
+    C: Compare<I::Item>,
+{
+    let slots: [Option<I::Item>; N] = core::array::from_fn(|_| iter.next());
+    let len = slots.iter().take_while(|slot| slot.is_some()).count();
+    if len < N {
+        return Err(SortError::WrongItemCount { capacity: N, len });
+    }
+    if iter.next().is_some() {
+        return Err(SortError::WrongItemCount {
+            capacity: N,
+            len: N + 1,
+        });
+    }
+
+    let mut items = slots.map(|slot| slot.unwrap());
+    for i in 1..N {
+        let (sorted, rest) = items.split_at(i);
+        let at = sorted.partition_point(|item| match compare.compare(item, &rest[0]) {
+            Ordering::Less => true,
+            Ordering::Equal => !newest_first,
+            Ordering::Greater => false,
+        });
+        items[at..=i].rotate_right(1);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SortByIteratorExt, SortError};
+
+    #[test]
+    fn sorts_into_array_stably() {
+        let input = [(2, 'a'), (1, 'b'), (2, 'c'), (0, 'd'), (1, 'e')];
+
+        let actual: [_; 5] = input.iter().copied().sort_by_into_array(|v| v.0).unwrap();
+        assert_eq!(actual, [(0, 'd'), (1, 'b'), (1, 'e'), (2, 'a'), (2, 'c')]);
+
+        let actual: [_; 5] = input
+            .iter()
+            .copied()
+            .sort_by_desc_into_array(|v| v.0)
+            .unwrap();
+        assert_eq!(actual, [(2, 'a'), (2, 'c'), (1, 'b'), (1, 'e'), (0, 'd')]);
+    }
+
+    #[test]
+    fn sorts_chained_levels_into_array() {
+        let input = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+
+        let actual: [_; 4] = input
+            .iter()
+            .copied()
+            .sort_by(|v| v.0)
+            .then_sort_by_desc(|v| v.1)
+            .into_sorted_array()
+            .unwrap();
+        assert_eq!(actual, [(1, 'd'), (1, 'b'), (2, 'c'), (2, 'a')]);
+
+        let actual: [_; 4] = input
+            .iter()
+            .copied()
+            .sort_by(|v| v.0)
+            .then_sort_by_input_order_desc()
+            .into_sorted_array()
+            .unwrap();
+        assert_eq!(actual, [(1, 'd'), (1, 'b'), (2, 'c'), (2, 'a')]);
+
+        let too_few: Result<[_; 5], _> = input.iter().sort_by(|v| v.0).into_sorted_array();
+        assert_eq!(
+            too_few,
+            Err(SortError::WrongItemCount {
+                capacity: 5,
+                len: 4
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_item_count() {
+        let too_few: Result<[i32; 4], _> = vec![3, 1, 2].into_iter().sort_by_into_array(|v| *v);
+        assert_eq!(
+            too_few,
+            Err(SortError::WrongItemCount {
+                capacity: 4,
+                len: 3
+            })
+        );
+
+        let too_many: Result<[u32; 4], _> = (0..).sort_by_into_array(|v| *v);
+        assert_eq!(
+            too_many,
+            Err(SortError::WrongItemCount {
+                capacity: 4,
+                len: 5
+            })
+        );
+    }
+}
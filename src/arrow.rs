@@ -0,0 +1,128 @@
+//! Sort indices for Arrow columns, so the same [`SortSpec`] that orders
+//! structs can order a [`RecordBatch`]. Requires the `arrow` feature.
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use arrow_array::{Int32Array, RecordBatch, StringArray};
+//! use sortby::arrow::{sort_record_batch, Nulls};
+//! use sortby::SortSpec;
+//!
+//! let batch = RecordBatch::try_from_iter([
+//!     ("age", Arc::new(Int32Array::from(vec![Some(30), None, Some(40), Some(30)])) as _),
+//!     ("name", Arc::new(StringArray::from(vec!["Jo", "Al", "Bob", "Ann"])) as _),
+//! ])
+//! .unwrap();
+//!
+//! let spec: SortSpec = "-age,name".parse().unwrap();
+//! let sorted = sort_record_batch(&batch, &spec, Nulls::Last).unwrap();
+//! let names = sorted.column(1).as_any().downcast_ref::<StringArray>().unwrap();
+//! assert_eq!(names.iter().flatten().collect::<Vec<_>>(), ["Bob", "Ann", "Jo", "Al"]);
+//! ```
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use arrow_array::{ArrayRef, RecordBatch, UInt32Array};
+use arrow_ord::ord::make_comparator;
+use arrow_schema::SortOptions;
+
+use crate::{Direction, SortError, SortSpec, UnknownField};
+
+/// Where null values of a column go, independent of the direction.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Nulls {
+    First,
+    #[default]
+    Last,
+}
+
+/// Computes the indices that sort `len` rows by `spec`, stable, for use with
+/// Arrow's `take` kernels. `column` returns the column of a field name, or
+/// `None` for names that cannot be sorted by, which fails with
+/// [`SortError::UnknownField`].
+pub fn take_indices<F>(
+    len: usize,
+    spec: &SortSpec,
+    nulls: Nulls,
+    mut column: F,
+) -> Result<UInt32Array, SortError>
+where
+    F: FnMut(&str) -> Option<ArrayRef>,
+{
+    let comparators = spec
+        .keys
+        .iter()
+        .map(|key| {
+            let array = column(&key.field).ok_or_else(|| UnknownField {
+                field: key.field.clone(),
+            })?;
+            let options = SortOptions {
+                descending: key.direction == Direction::Descending,
+                nulls_first: nulls == Nulls::First,
+            };
+            Ok(make_comparator(&array, &array, options)?)
+        })
+        .collect::<Result<Vec<_>, SortError>>()?;
+
+    let mut indices: Vec<u32> = (0..len as u32).collect();
+    indices.sort_by(|&a, &b| {
+        comparators
+            .iter()
+            .map(|compare| compare(a as usize, b as usize))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+    Ok(UInt32Array::from(indices))
+}
+
+/// Sorts the rows of `batch` by `spec`, looking up fields by column name.
+/// Columns Arrow cannot compare or take fail with [`SortError::Arrow`].
+pub fn sort_record_batch(
+    batch: &RecordBatch,
+    spec: &SortSpec,
+    nulls: Nulls,
+) -> Result<RecordBatch, SortError> {
+    let indices = take_indices(batch.num_rows(), spec, nulls, |field| {
+        batch.column_by_name(field).cloned()
+    })?;
+    Ok(arrow_select::take::take_record_batch(batch, &indices)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use arrow_array::{Array, Float64Array, Int32Array};
+
+    use super::{take_indices, Nulls};
+    use crate::{SortError, SortSpec};
+
+    #[test]
+    fn places_nulls_and_breaks_ties() {
+        let score: Arc<dyn Array> = Arc::new(Float64Array::from(vec![
+            Some(1.5),
+            None,
+            Some(2.5),
+            Some(1.5),
+        ]));
+        let id: Arc<dyn Array> = Arc::new(Int32Array::from(vec![4, 3, 2, 1]));
+        let column = |field: &str| match field {
+            "score" => Some(score.clone()),
+            "id" => Some(id.clone()),
+            _ => None,
+        };
+
+        let spec: SortSpec = "-score,id".parse().unwrap();
+        let last = take_indices(4, &spec, Nulls::Last, column).unwrap();
+        assert_eq!(last.values(), &[2, 3, 0, 1]);
+        let first = take_indices(4, &spec, Nulls::First, column).unwrap();
+        assert_eq!(first.values(), &[1, 2, 3, 0]);
+
+        let spec: SortSpec = "rank".parse().unwrap();
+        assert!(matches!(
+            take_indices(4, &spec, Nulls::Last, column),
+            Err(SortError::UnknownField(_))
+        ));
+    }
+}
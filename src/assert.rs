@@ -0,0 +1,151 @@
+//! Assertions for tests that check an ordering and, on failure, report the
+//! first offending pair with its indices.
+//!
+//! ```
+//! use sortby::{assert_sorted_by, assert_sorted_spec, SortSpec};
+//!
+//! assert_sorted_by!(vec![1, 2, 2, 5], |v| *v);
+//!
+//! let spec: SortSpec = "-age".parse().unwrap();
+//! let compare = spec
+//!     .compare_fn(|_| Some(std::sync::Arc::new(|a: &u32, b: &u32| a.cmp(b))))
+//!     .unwrap();
+//! assert_sorted_spec!([40, 30, 30], compare);
+//! ```
+
+use core::cmp::Ordering;
+
+/// The first pair of neighboring items that is out of order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unsorted<T> {
+    /// Zero-based position of `item`; `previous` is at `index - 1`.
+    pub index: usize,
+    pub previous: T,
+    pub item: T,
+}
+
+impl<T: core::fmt::Debug> core::fmt::Display for Unsorted<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "items at indices {} and {} are out of order\n  [{}]: {:?}\n  [{}]: {:?}",
+            self.index - 1,
+            self.index,
+            self.index - 1,
+            self.previous,
+            self.index,
+            self.item
+        )
+    }
+}
+
+/// Returns the first pair of neighbors whose keys are in descending order.
+pub fn find_unsorted_by<I, F, V>(items: I, key: F) -> Option<Unsorted<I::Item>>
+where
+    I: IntoIterator,
+    V: PartialOrd,
+    F: Fn(&I::Item) -> V,
+{
+    find_unsorted_with(items, |a, b| {
+        key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal)
+    })
+}
+
+/// Returns the first pair of neighbors that `compare` orders the other way
+/// around.
+pub fn find_unsorted_with<I, F>(items: I, compare: F) -> Option<Unsorted<I::Item>>
+where
+    I: IntoIterator,
+    F: Fn(&I::Item, &I::Item) -> Ordering,
+{
+    let mut items = items.into_iter().enumerate();
+    let (_, mut previous) = items.next()?;
+    for (index, item) in items {
+        if compare(&previous, &item) == Ordering::Greater {
+            return Some(Unsorted {
+                index,
+                previous,
+                item,
+            });
+        }
+        previous = item;
+    }
+    None
+}
+
+/// Asserts that the items of an `IntoIterator` are in ascending order of a
+/// key, reporting the first offending pair otherwise. Items must be `Debug`.
+#[macro_export]
+macro_rules! assert_sorted_by {
+    ($items:expr, $key:expr $(,)?) => {
+        if let Some(unsorted) = $crate::assert::find_unsorted_by($items, $key) {
+            panic!("assertion failed: sorted by key\n{}", unsorted);
+        }
+    };
+    ($items:expr, $key:expr, $($arg:tt)+) => {
+        if let Some(unsorted) = $crate::assert::find_unsorted_by($items, $key) {
+            panic!(
+                "assertion failed: sorted by key: {}\n{}",
+                format_args!($($arg)+),
+                unsorted
+            );
+        }
+    };
+}
+
+/// Asserts that the items of an `IntoIterator` are in the order of a
+/// comparator, e.g. one built by
+/// [`SortSpec::compare_fn`](crate::SortSpec::compare_fn), reporting the first
+/// offending pair otherwise. Items must be `Debug`.
+#[macro_export]
+macro_rules! assert_sorted_spec {
+    ($items:expr, $compare:expr $(,)?) => {{
+        let compare = $compare;
+        if let Some(unsorted) = $crate::assert::find_unsorted_with($items, |a, b| compare(a, b)) {
+            panic!("assertion failed: sorted by spec\n{}", unsorted);
+        }
+    }};
+    ($items:expr, $compare:expr, $($arg:tt)+) => {{
+        let compare = $compare;
+        if let Some(unsorted) = $crate::assert::find_unsorted_with($items, |a, b| compare(a, b)) {
+            panic!(
+                "assertion failed: sorted by spec: {}\n{}",
+                format_args!($($arg)+),
+                unsorted
+            );
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_unsorted_by, Unsorted};
+
+    #[test]
+    fn finds_first_unsorted_pair() {
+        assert_eq!(find_unsorted_by([1, 1, 2], |v| *v), None);
+        assert_eq!(find_unsorted_by(Vec::<i32>::new(), |v| *v), None);
+        assert_eq!(
+            find_unsorted_by([1, 3, 2, 0], |v| *v),
+            Some(Unsorted {
+                index: 2,
+                previous: 3,
+                item: 2
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "items at indices 1 and 2 are out of order\n  [1]: (3, 'b')\n  [2]: (2, 'c')"
+    )]
+    fn reports_offending_pair() {
+        crate::assert_sorted_by!([(1, 'a'), (3, 'b'), (2, 'c')], |v| v.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted by spec: newest first")]
+    fn reports_custom_message() {
+        crate::assert_sorted_spec!([1, 2], |a: &i32, b: &i32| b.cmp(a), "newest {}", "first");
+    }
+}
@@ -0,0 +1,141 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Groups `items` into the buckets `bucket` assigns them to, in ascending
+/// bucket order, keeping the input order within each bucket.
+fn distribute<T, B, G>(items: impl Iterator<Item = T>, bucket: G) -> Vec<Vec<T>>
+where
+    B: Ord,
+    G: Fn(&T) -> B,
+{
+    let mut buckets = BTreeMap::new();
+    for item in items {
+        buckets
+            .entry(bucket(&item))
+            .or_insert_with(Vec::new)
+            .push(item);
+    }
+    buckets.into_values().collect()
+}
+
+fn concat<T>(buckets: Vec<Vec<T>>) -> Vec<T> {
+    let mut items = Vec::with_capacity(buckets.iter().map(Vec::len).sum());
+    for bucket in buckets {
+        items.extend(bucket);
+    }
+    items
+}
+
+/// Stably sorts each bucket by `key` and concatenates them in bucket order,
+/// see [`SortByIteratorExt::bucket_sort_by`](crate::SortByIteratorExt::bucket_sort_by).
+pub(crate) fn bucket_sort_by<T, B, G, V, F>(
+    items: impl Iterator<Item = T>,
+    bucket: G,
+    key: F,
+) -> Vec<T>
+where
+    B: Ord,
+    G: Fn(&T) -> B,
+    V: PartialOrd,
+    F: Fn(&T) -> V,
+{
+    let mut buckets = distribute(items, bucket);
+    for bucket in &mut buckets {
+        bucket.sort_by(|a, b| key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal));
+    }
+    concat(buckets)
+}
+
+/// Like [`bucket_sort_by`], but sorts the buckets in parallel where
+/// `parallelism` says.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_bucket_sort_by<T, B, G, V, F>(
+    items: impl Iterator<Item = T>,
+    bucket: G,
+    key: F,
+    parallelism: crate::Parallelism<'_>,
+) -> Vec<T>
+where
+    T: Send,
+    B: Ord,
+    G: Fn(&T) -> B,
+    V: PartialOrd,
+    F: Fn(&T) -> V + Sync,
+{
+    let mut buckets = distribute(items, bucket);
+    parallelism.for_each_mut(&mut buckets, |bucket| {
+        bucket.sort_by(|a, b| key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal));
+    });
+    concat(buckets)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn sorts_within_buckets_in_bucket_order() {
+        let input = vec![(2, 'b'), (1, 'z'), (2, 'a'), (0, 'q'), (1, 'c'), (2, 'a')];
+
+        let actual: Vec<_> = input
+            .clone()
+            .into_iter()
+            .bucket_sort_by(|v| v.0, |v| v.1)
+            .collect();
+        assert_eq!(
+            actual,
+            vec![(0, 'q'), (1, 'c'), (1, 'z'), (2, 'a'), (2, 'a'), (2, 'b')]
+        );
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            input
+                .into_iter()
+                .par_bucket_sort_by(|v| v.0, |v| v.1)
+                .collect::<Vec<_>>(),
+            actual
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_output_matches_serial_for_any_thread_count() {
+        use crate::Parallelism;
+
+        // few distinct keys, so stability decides most of the order
+        let input: Vec<(u32, u32, usize)> = (0..20_000)
+            .map(|i| ((i * 7919) % 64, (i * 31) % 5, i as usize))
+            .collect();
+
+        let serial: Vec<_> = input
+            .clone()
+            .into_iter()
+            .bucket_sort_by(|v| v.0 / 8, |v| v.1)
+            .collect();
+        for pair in serial.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert!(
+                (a.0 / 8, a.1) < (b.0 / 8, b.1) || a.2 < b.2,
+                "{:?} {:?}",
+                a,
+                b
+            );
+        }
+
+        for parallelism in [
+            Parallelism::Global,
+            Parallelism::Threads(1),
+            Parallelism::Threads(3),
+            Parallelism::Threads(16),
+            Parallelism::Serial,
+        ] {
+            let parallel: Vec<_> = input
+                .clone()
+                .into_iter()
+                .par_bucket_sort_by_in(|v| v.0 / 8, |v| v.1, parallelism)
+                .collect();
+            assert_eq!(parallel, serial, "{:?}", parallelism);
+        }
+    }
+}
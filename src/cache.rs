@@ -0,0 +1,92 @@
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+/// The sorted items behind a reference count, so any number of consumers,
+/// also on other threads, can iterate over the same order without sorting
+/// or copying it again.
+///
+/// Created by [`SortBy::into_cached`](crate::SortBy::into_cached). Cloning
+/// is cheap and shares the items.
+///
+/// ```
+/// use sortby::*;
+///
+/// let ranking = vec![("b", 2), ("a", 3), ("c", 1)]
+///     .into_iter()
+///     .sort_by_desc(|v| v.1)
+///     .into_cached();
+///
+/// let names: Vec<_> = ranking.iter().map(|v| v.0).collect();
+/// let total: i32 = ranking.iter().map(|v| v.1).sum();
+/// assert_eq!(names, vec!["a", "b", "c"]);
+/// assert_eq!(total, 6);
+/// ```
+#[derive(Debug)]
+pub struct SortedCache<T> {
+    items: Arc<[T]>,
+}
+
+impl<T> SortedCache<T> {
+    pub(crate) fn new(items: Arc<[T]>) -> SortedCache<T> {
+        SortedCache { items }
+    }
+
+    /// A fresh iterator over the items in sorted order.
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The shared items, e.g. to hand them to code that takes an `Arc`.
+    pub fn into_shared(self) -> Arc<[T]> {
+        self.items
+    }
+}
+
+impl<T> Clone for SortedCache<T> {
+    fn clone(&self) -> Self {
+        SortedCache {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T> Deref for SortedCache<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<'s, T> IntoIterator for &'s SortedCache<T> {
+    type Item = &'s T;
+    type IntoIter = core::slice::Iter<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn shares_items_between_clones_and_threads() {
+        let cache = vec![3, 1, 2].into_iter().sort_by(|v| *v).into_cached();
+        let copy = cache.clone();
+
+        let total = std::thread::spawn(move || copy.iter().sum::<i32>())
+            .join()
+            .unwrap();
+        assert_eq!(total, 6);
+        assert_eq!((&cache).into_iter().collect::<Vec<_>>(), [&1, &2, &3]);
+        assert_eq!(cache.into_shared().len(), 3);
+    }
+}
@@ -0,0 +1,96 @@
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use regex::Regex;
+
+use crate::Compare;
+
+/// Orders strings by a parsed capture group of a regex, see
+/// [`SortByIteratorExt::sort_by_regex_capture`](crate::SortByIteratorExt::sort_by_regex_capture).
+///
+/// Strings the regex does not match, or where the group did not
+/// participate in the match, come after all others.
+pub struct ByRegexCapture<'r, P, K> {
+    pattern: &'r Regex,
+    group: usize,
+    parse: P,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<'r, P, K> ByRegexCapture<'r, P, K> {
+    pub(crate) fn new(pattern: &'r Regex, group: usize, parse: P) -> ByRegexCapture<'r, P, K> {
+        ByRegexCapture {
+            pattern,
+            group,
+            parse,
+            key: PhantomData,
+        }
+    }
+
+    fn key(&self, s: &str) -> Option<K>
+    where
+        P: Fn(&str) -> K,
+    {
+        let capture = self.pattern.captures(s)?.get(self.group)?;
+        Some((self.parse)(capture.as_str()))
+    }
+}
+
+impl<'r, T, P, K> Compare<T> for ByRegexCapture<'r, P, K>
+where
+    T: AsRef<str>,
+    P: Fn(&str) -> K,
+    K: PartialOrd,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        match (self.key(a.as_ref()), self.key(b.as_ref())) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl<'r, P: Clone, K> Clone for ByRegexCapture<'r, P, K> {
+    fn clone(&self) -> Self {
+        ByRegexCapture::new(self.pattern, self.group, self.parse.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn sorts_by_parsed_capture() {
+        let pattern = Regex::new(r"frame_(\d+)\.png").unwrap();
+        let input = vec!["frame_10.png", "notes.txt", "frame_9.png", "frame_100.png"];
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by_regex_capture(&pattern, 1, |s| s.parse::<u32>().ok())
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec!["frame_9.png", "frame_10.png", "frame_100.png", "notes.txt"]
+        );
+    }
+
+    #[test]
+    fn breaks_ties_by_another_capture() {
+        let pattern = Regex::new(r"^(\w+) (\w+)").unwrap();
+        let input = vec!["b WARN", "a INFO", "c INFO"];
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by_regex_capture(&pattern, 2, str::to_owned)
+            .then_sort_by_regex_capture(&pattern, 1, str::to_owned)
+            .collect();
+
+        assert_eq!(actual, vec!["a INFO", "c INFO", "b WARN"]);
+    }
+}
@@ -0,0 +1,186 @@
+use core::cmp::Ordering;
+
+/// Passes items through unchanged and panics on the first item whose key is
+/// smaller than its predecessor's, see
+/// [`SortByIteratorExt::assert_sorted_by`](crate::SortByIteratorExt::assert_sorted_by).
+pub struct AssertSorted<I, F, V> {
+    inner: CheckSorted<I, F, V>,
+}
+
+/// Passes items through as `Ok` and wraps every item whose key is smaller
+/// than its predecessor's in an `Err`, see
+/// [`SortByIteratorExt::check_sorted_by`](crate::SortByIteratorExt::check_sorted_by).
+pub struct CheckSorted<I, F, V> {
+    iter: I,
+    key: F,
+    previous: Option<V>,
+    index: usize,
+}
+
+impl<I, F, V> CheckSorted<I, F, V> {
+    pub(crate) fn new(iter: I, key: F) -> CheckSorted<I, F, V> {
+        CheckSorted {
+            iter,
+            key,
+            previous: None,
+            index: 0,
+        }
+    }
+}
+
+impl<I, F, V> AssertSorted<I, F, V> {
+    pub(crate) fn new(iter: I, key: F) -> AssertSorted<I, F, V> {
+        AssertSorted {
+            inner: CheckSorted::new(iter, key),
+        }
+    }
+}
+
+impl<I, F, V> Iterator for CheckSorted<I, F, V>
+where
+    I: Iterator,
+    V: PartialOrd,
+    F: Fn(&I::Item) -> V,
+{
+    type Item = Result<I::Item, OutOfOrder<I::Item>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let key = (self.key)(&item);
+        let index = self.index;
+        self.index += 1;
+
+        let out_of_order = matches!(
+            self.previous
+                .as_ref()
+                .and_then(|previous| previous.partial_cmp(&key)),
+            Some(Ordering::Greater)
+        );
+        self.previous = Some(key);
+        if out_of_order {
+            Some(Err(OutOfOrder { index, item }))
+        } else {
+            Some(Ok(item))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, F, V> Iterator for AssertSorted<I, F, V>
+where
+    I: Iterator,
+    V: PartialOrd,
+    F: Fn(&I::Item) -> V,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner.next()? {
+            Ok(item) => Some(item),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A run of consecutive items that is already in order, see
+/// [`SortBy::longest_run`](crate::SortBy::longest_run).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Run {
+    /// Zero-based position of the run's first item.
+    pub start: usize,
+    pub len: usize,
+}
+
+/// An item whose key is smaller than its predecessor's.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutOfOrder<T> {
+    /// Zero-based position of `item` in the input.
+    pub index: usize,
+    pub item: T,
+}
+
+impl<T> core::fmt::Display for OutOfOrder<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "item at index {} sorts before its predecessor",
+            self.index
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: core::fmt::Debug> std::error::Error for OutOfOrder<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutOfOrder, Run};
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn flags_out_of_order_items() {
+        let input = vec![1, 2, 2, 1, 3, 0];
+
+        let actual: Vec<_> = input.into_iter().check_sorted_by(|v| *v).collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                Ok(1),
+                Ok(2),
+                Ok(2),
+                Err(OutOfOrder { index: 3, item: 1 }),
+                Ok(3),
+                Err(OutOfOrder { index: 5, item: 0 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_longest_sorted_run() {
+        let input = vec![
+            (5, 'a'),
+            (1, 'b'),
+            (2, 'c'),
+            (2, 'a'),
+            (3, 'd'),
+            (0, 'e'),
+            (1, 'f'),
+        ];
+
+        assert_eq!(
+            input.clone().into_iter().longest_run_by(|v| v.0),
+            Run { start: 1, len: 4 }
+        );
+
+        let mut sort = input.into_iter().sort_by(|v| v.0).then_sort_by(|v| v.1);
+        assert_eq!(sort.longest_run(), Run { start: 1, len: 2 });
+        assert_eq!(sort.next(), Some((0, 'e')));
+
+        assert_eq!(
+            core::iter::empty::<u8>().longest_run_by(|v| *v),
+            Run { start: 0, len: 0 }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "item at index 2 sorts before its predecessor")]
+    fn asserts_sorted_order() {
+        let input = [3, 2, 1];
+
+        let passed: Vec<_> = input.iter().assert_sorted_by(|v| -**v).collect();
+        assert_eq!(passed, vec![&3, &2, &1]);
+
+        vec![1, 2, 0]
+            .into_iter()
+            .assert_sorted_by(|v| *v)
+            .for_each(drop);
+    }
+}
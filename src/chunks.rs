@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::sorter::{self, Strategy};
+use crate::{Compare, CompareFn, IterState};
+
+/// Streams an iterator as independently sorted chunks, see
+/// [`SortBy::sort_within_chunks`](crate::SortBy::sort_within_chunks).
+pub struct SortedChunks<'a, I: Iterator, C = CompareFn<'a, <I as Iterator>::Item>> {
+    iter: IterState<I>,
+    compare: C,
+    size: usize,
+    strategy: Strategy,
+    newest_first: bool,
+    chunk: alloc::vec::IntoIter<I::Item>,
+    done: bool,
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a, I, C> SortedChunks<'a, I, C>
+where
+    I: Iterator,
+    C: Compare<I::Item>,
+{
+    pub(crate) fn new(
+        iter: IterState<I>,
+        compare: C,
+        size: usize,
+        strategy: Strategy,
+        newest_first: bool,
+    ) -> SortedChunks<'a, I, C> {
+        SortedChunks {
+            iter,
+            compare,
+            size,
+            strategy,
+            newest_first,
+            chunk: Vec::new().into_iter(),
+            done: false,
+            lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a, I, C> Iterator for SortedChunks<'a, I, C>
+where
+    I: Iterator,
+    C: Compare<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.chunk.next() {
+            return Some(item);
+        }
+        if self.done {
+            return None;
+        }
+
+        // the size hint keeps huge chunk sizes from allocating up front
+        let (lower, upper) = match self.iter {
+            IterState::Unsorted(Some(ref iter)) => iter.size_hint(),
+            IterState::Unsorted(None) => (0, Some(0)),
+            IterState::Sorted(ref iter) => iter.size_hint(),
+        };
+        let mut chunk = Vec::with_capacity(self.size.min(upper.unwrap_or(lower)));
+        match self.iter {
+            IterState::Unsorted(Some(ref mut iter)) => chunk.extend(iter.by_ref().take(self.size)),
+            IterState::Unsorted(None) => {}
+            IterState::Sorted(ref mut iter) => chunk.extend(iter.by_ref().take(self.size)),
+        }
+        self.done = chunk.len() < self.size;
+        sorter::sort_items(&mut chunk, self.strategy, self.newest_first, |a, b| {
+            self.compare.compare(a, b)
+        });
+        self.chunk = chunk.into_iter();
+        self.chunk.next()
+    }
+}
+
+/// The globally sorted items in consecutive pages, see
+/// [`SortBy::sorted_chunks`](crate::SortBy::sorted_chunks).
+pub struct SortedPages<T> {
+    items: alloc::vec::IntoIter<T>,
+    size: usize,
+}
+
+impl<T> SortedPages<T> {
+    pub(crate) fn new(items: Vec<T>, size: usize) -> SortedPages<T> {
+        SortedPages {
+            items: items.into_iter(),
+            size,
+        }
+    }
+}
+
+impl<T> Iterator for SortedPages<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let page: Vec<T> = self.items.by_ref().take(self.size).collect();
+        if page.is_empty() {
+            None
+        } else {
+            Some(page)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pages = self.items.len().div_ceil(self.size);
+        (pages, Some(pages))
+    }
+}
+
+impl<T> ExactSizeIterator for SortedPages<T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn sorts_each_chunk_independently() {
+        let input = vec![3, 1, 2, 9, 8, 7, 5, 4];
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by(|v| *v)
+            .sort_within_chunks(3)
+            .collect();
+
+        assert_eq!(actual, vec![1, 2, 3, 7, 8, 9, 4, 5]);
+    }
+
+    #[test]
+    fn accepts_chunks_larger_than_the_input() {
+        let input = vec![3, 1, 2];
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by(|v| *v)
+            .sort_within_chunks(usize::MAX)
+            .collect();
+
+        assert_eq!(actual, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn pages_the_global_order() {
+        let input = vec![3, 1, 2, 9, 8, 7, 5, 4];
+
+        let pages = input.into_iter().sort_by(|v| *v).sorted_chunks(3);
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(
+            pages.collect::<Vec<_>>(),
+            vec![vec![1, 2, 3], vec![4, 5, 7], vec![8, 9]]
+        );
+    }
+}
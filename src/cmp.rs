@@ -0,0 +1,135 @@
+//! Free comparator combinators. Each returns a plain
+//! `Fn(&T, &T) -> Ordering`, so the results work with the std slice sorts
+//! and searches as well as with each other.
+//!
+//! ```
+//! use sortby::cmp::{by_key, by_key_desc, then};
+//!
+//! let mut data = vec![(1, "b"), (2, "a"), (1, "a")];
+//! data.sort_by(then(by_key_desc(|v: &(i32, &str)| v.0), by_key(|v: &(i32, &str)| v.1)));
+//! assert_eq!(data, vec![(2, "a"), (1, "a"), (1, "b")]);
+//! ```
+
+use core::cmp::Ordering;
+
+use crate::numbers::Digits;
+
+/// Orders by the key `f` returns, treating incomparable keys as equal.
+pub fn by_key<T, K, F>(f: F) -> impl Fn(&T, &T) -> Ordering
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    move |a, b| f(a).partial_cmp(&f(b)).unwrap_or(Ordering::Equal)
+}
+
+/// Orders by the key `f` returns, largest first.
+pub fn by_key_desc<T, K, F>(f: F) -> impl Fn(&T, &T) -> Ordering
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    move |a, b| f(b).partial_cmp(&f(a)).unwrap_or(Ordering::Equal)
+}
+
+/// Orders by `a`, breaking ties with `b`.
+pub fn then<T, A, B>(a: A, b: B) -> impl Fn(&T, &T) -> Ordering
+where
+    A: Fn(&T, &T) -> Ordering,
+    B: Fn(&T, &T) -> Ordering,
+{
+    move |x, y| a(x, y).then_with(|| b(x, y))
+}
+
+/// Inverts `compare`.
+pub fn reverse<T, C>(compare: C) -> impl Fn(&T, &T) -> Ordering
+where
+    C: Fn(&T, &T) -> Ordering,
+{
+    move |a, b| compare(b, a)
+}
+
+/// Orders `Option`s by `compare`, with all `None`s after the `Some`s.
+pub fn nulls_last<T, C>(compare: C) -> impl Fn(&Option<T>, &Option<T>) -> Ordering
+where
+    C: Fn(&T, &T) -> Ordering,
+{
+    move |a, b| match (a, b) {
+        (Some(a), Some(b)) => compare(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Orders by the number in the string `f` returns, of any length, e.g. IDs
+/// with more digits than a `u64` holds. Numbers are compared by sign, then
+/// magnitude, then digits, without being parsed into a fixed-width type.
+/// Strings that are not plain numbers like `-0012.50` come last, ordered as
+/// text. For numbers with group separators, see
+/// [`NumberFormat::key`](crate::NumberFormat::key).
+pub fn by_numeric_str<T, F>(f: F) -> impl Fn(&T, &T) -> Ordering
+where
+    F: Fn(&T) -> &str,
+{
+    move |a, b| {
+        let (a, b) = (f(a).trim(), f(b).trim());
+        match (Digits::parse(a), Digits::parse(b)) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.cmp(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composes_combinators() {
+        let mut data = vec![(2, 'b'), (1, 'a'), (2, 'a'), (1, 'c')];
+        data.sort_by(then(
+            by_key(|v: &(i32, char)| v.0),
+            reverse(by_key(|v: &(i32, char)| v.1)),
+        ));
+
+        assert_eq!(data, vec![(1, 'c'), (1, 'a'), (2, 'b'), (2, 'a')]);
+    }
+
+    #[test]
+    fn sorts_nones_last() {
+        let mut data = vec![None, Some(2), Some(3), None, Some(1)];
+        data.sort_by(nulls_last(by_key_desc(|v: &i32| *v)));
+
+        assert_eq!(data, vec![Some(3), Some(2), Some(1), None, None]);
+    }
+
+    #[test]
+    fn compares_long_numbers_by_value() {
+        let mut data = vec![
+            "123456789012345678901234567890",
+            "x",
+            "-99999999999999999999999999999999",
+            "99999999999999999999",
+            "0000123456789012345678901234567890.5",
+            "-0",
+            "1e5",
+        ];
+        data.sort_by(by_numeric_str(|v: &&str| *v));
+
+        assert_eq!(
+            data,
+            vec![
+                "-99999999999999999999999999999999",
+                "-0",
+                "99999999999999999999",
+                "123456789012345678901234567890",
+                "0000123456789012345678901234567890.5",
+                "1e5",
+                "x"
+            ]
+        );
+    }
+}
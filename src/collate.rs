@@ -0,0 +1,195 @@
+//! Locale-aware string ordering backed by ICU4X. Requires the `icu` feature.
+//!
+//! ```
+//! use sortby::*;
+//! use sortby::collate::{Collation, Strength};
+//!
+//! let collator = Collation::new("de").strength(Strength::Secondary).numeric(true).build().unwrap();
+//!
+//! let files = vec!["Datei 10", "datei 9", "Äpfel", "Zebra"];
+//! let sorted: Vec<_> = files.into_iter().sort_by_collated(|v| *v, &collator).collect();
+//! assert_eq!(sorted, vec!["Äpfel", "datei 9", "Datei 10", "Zebra"]);
+//! ```
+
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+
+use icu_collator::options::{self, CollatorOptions};
+use icu_collator::preferences::CollationNumericOrdering;
+use icu_collator::{CollatorBorrowed, CollatorPreferences};
+use icu_locale_core::Locale;
+
+use crate::Compare;
+
+/// Which differences between strings a [`Collator`] considers, from the
+/// most significant on. Each strength includes the ones before it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Strength {
+    /// Base letters only, so `a`, `A` and `á` are equal.
+    Primary,
+    /// Also accents, so `a` and `A` are equal, but `á` is not.
+    Secondary,
+    /// Also case, so case differences matter only as a last resort. This is
+    /// the default.
+    Tertiary,
+    /// Also punctuation when it is ignored otherwise.
+    Quaternary,
+    /// Breaks every remaining tie by code point.
+    Identical,
+}
+
+/// Configures a [`Collator`] for a locale.
+#[derive(Clone, Debug)]
+pub struct Collation {
+    locale: String,
+    strength: Option<Strength>,
+    numeric: bool,
+}
+
+impl Collation {
+    /// Starts from the defaults of `locale`, a BCP 47 tag like `de` or
+    /// `sv-SE`.
+    pub fn new(locale: &str) -> Collation {
+        Collation {
+            locale: locale.to_string(),
+            strength: None,
+            numeric: false,
+        }
+    }
+
+    pub fn strength(mut self, strength: Strength) -> Collation {
+        self.strength = Some(strength);
+        self
+    }
+
+    /// Compares runs of digits by their numeric value, so `file9` comes
+    /// before `file10`.
+    pub fn numeric(mut self, numeric: bool) -> Collation {
+        self.numeric = numeric;
+        self
+    }
+
+    pub fn build(&self) -> Result<Collator, InvalidLocale> {
+        let invalid = || InvalidLocale {
+            locale: self.locale.clone(),
+        };
+        let locale: Locale = self.locale.parse().map_err(|_| invalid())?;
+        let mut preferences = CollatorPreferences::from(&locale);
+        if self.numeric {
+            preferences.numeric_ordering = Some(CollationNumericOrdering::True);
+        }
+        let mut options = CollatorOptions::default();
+        options.strength = self.strength.map(|strength| match strength {
+            Strength::Primary => options::Strength::Primary,
+            Strength::Secondary => options::Strength::Secondary,
+            Strength::Tertiary => options::Strength::Tertiary,
+            Strength::Quaternary => options::Strength::Quaternary,
+            Strength::Identical => options::Strength::Identical,
+        });
+        let inner = CollatorBorrowed::try_new(preferences, options).map_err(|_| invalid())?;
+        Ok(Collator { inner })
+    }
+}
+
+/// Compares strings by the rules of a locale, see [`Collation`].
+#[derive(Debug)]
+pub struct Collator {
+    inner: CollatorBorrowed<'static>,
+}
+
+impl Collator {
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.inner.compare(a, b)
+    }
+}
+
+/// Orders by a string key using a [`Collator`], see
+/// [`SortByIteratorExt::sort_by_collated`](crate::SortByIteratorExt::sort_by_collated).
+pub struct ByCollatedKey<'c, F> {
+    key: F,
+    collator: &'c Collator,
+}
+
+impl<'c, F> ByCollatedKey<'c, F> {
+    pub(crate) fn new(key: F, collator: &'c Collator) -> ByCollatedKey<'c, F> {
+        ByCollatedKey { key, collator }
+    }
+}
+
+impl<'c, T, F> Compare<T> for ByCollatedKey<'c, F>
+where
+    F: Fn(&T) -> &str,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.collator.compare((self.key)(a), (self.key)(b))
+    }
+}
+
+impl<'c, F: Clone> Clone for ByCollatedKey<'c, F> {
+    fn clone(&self) -> Self {
+        ByCollatedKey::new(self.key.clone(), self.collator)
+    }
+}
+
+/// Returned by [`Collation::build`] for a locale that is malformed or has no
+/// collation data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidLocale {
+    pub locale: String,
+}
+
+impl core::fmt::Display for InvalidLocale {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "cannot collate for locale `{}`", self.locale)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidLocale {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn applies_strength() {
+        let tertiary = Collation::new("en").build().unwrap();
+        assert_eq!(tertiary.compare("a", "A"), Ordering::Less);
+        assert_eq!(tertiary.compare("A", "b"), Ordering::Less);
+
+        let secondary = Collation::new("en")
+            .strength(Strength::Secondary)
+            .build()
+            .unwrap();
+        assert_eq!(secondary.compare("a", "A"), Ordering::Equal);
+        assert_eq!(secondary.compare("émile", "Emile"), Ordering::Greater);
+
+        let primary = Collation::new("en")
+            .strength(Strength::Primary)
+            .build()
+            .unwrap();
+        assert_eq!(primary.compare("émile", "Emile"), Ordering::Equal);
+
+        assert_eq!(
+            Collation::new("not a locale").build().unwrap_err(),
+            InvalidLocale {
+                locale: "not a locale".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn compares_numbers_by_value() {
+        let input = vec![("a10", 1), ("a9", 2), ("a10", 0)];
+        let collator = Collation::new("en").numeric(true).build().unwrap();
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by_collated(|v| v.0, &collator)
+            .then_sort_by(|v| v.1)
+            .collect();
+
+        assert_eq!(actual, vec![("a9", 2), ("a10", 0), ("a10", 1)]);
+    }
+}
@@ -0,0 +1,147 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::{Direction, SortKey, SortSpec};
+
+/// The sort state of a table UI with clickable column headers.
+///
+/// A click cycles a column through ascending, descending and unsorted, and
+/// makes it the only sorted column. A shift-click cycles the column in
+/// place instead, appending it as the least significant column if it was
+/// not sorted yet, so the other columns are kept.
+///
+/// ```
+/// use sortby::*;
+///
+/// let mut state = ColumnSortState::new();
+/// state.click("name");
+/// state.shift_click("age");
+/// state.shift_click("age");
+/// assert_eq!(state.spec().to_string(), "name,-age");
+///
+/// state.click("age");
+/// assert_eq!(state.spec().to_string(), "");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ColumnSortState {
+    keys: Vec<SortKey>,
+}
+
+impl ColumnSortState {
+    pub fn new() -> ColumnSortState {
+        ColumnSortState::default()
+    }
+
+    /// Cycles `column` and clears every other column.
+    pub fn click(&mut self, column: &str) {
+        let next = next_direction(self.direction(column));
+        self.keys.clear();
+        if let Some(direction) = next {
+            self.keys.push(SortKey {
+                field: column.to_string(),
+                direction,
+            });
+        }
+    }
+
+    /// Cycles `column` while keeping every other column.
+    pub fn shift_click(&mut self, column: &str) {
+        match self.position(column) {
+            Some(i) => match next_direction(Some(self.keys[i].direction)) {
+                Some(direction) => self.keys[i].direction = direction,
+                None => {
+                    self.keys.remove(i);
+                }
+            },
+            None => self.keys.push(SortKey {
+                field: column.to_string(),
+                direction: Direction::Ascending,
+            }),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// The direction `column` is sorted in, if it is sorted, e.g. to pick the
+    /// arrow shown in its header.
+    pub fn direction(&self, column: &str) -> Option<Direction> {
+        self.position(column).map(|i| self.keys[i].direction)
+    }
+
+    /// The zero-based significance of `column`, if it is sorted, e.g. for the
+    /// small "1", "2" badges of multi-column sorts.
+    pub fn priority(&self, column: &str) -> Option<usize> {
+        self.position(column)
+    }
+
+    /// The sort order the state currently describes.
+    pub fn spec(&self) -> SortSpec {
+        SortSpec {
+            keys: self.keys.clone(),
+        }
+    }
+
+    fn position(&self, column: &str) -> Option<usize> {
+        self.keys.iter().position(|key| key.field == column)
+    }
+}
+
+impl From<SortSpec> for ColumnSortState {
+    fn from(spec: SortSpec) -> Self {
+        let mut state = ColumnSortState::new();
+        for key in spec.keys {
+            if state.position(&key.field).is_none() {
+                state.keys.push(key);
+            }
+        }
+        state
+    }
+}
+
+/// unsorted -> ascending -> descending -> unsorted
+fn next_direction(direction: Option<Direction>) -> Option<Direction> {
+    match direction {
+        None => Some(Direction::Ascending),
+        Some(Direction::Ascending) => Some(Direction::Descending),
+        Some(Direction::Descending) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_and_appends_columns() {
+        let mut state = ColumnSortState::new();
+        state.click("name");
+        assert_eq!(state.direction("name"), Some(Direction::Ascending));
+        state.click("name");
+        assert_eq!(state.direction("name"), Some(Direction::Descending));
+
+        state.shift_click("age");
+        state.shift_click("city");
+        assert_eq!(state.spec().to_string(), "-name,age,city");
+        assert_eq!(state.priority("city"), Some(2));
+
+        state.shift_click("name");
+        assert_eq!(state.spec().to_string(), "age,city");
+        assert_eq!(state.priority("name"), None);
+
+        state.click("city");
+        assert_eq!(state.spec().to_string(), "-city");
+        state.click("city");
+        assert_eq!(state, ColumnSortState::new());
+    }
+
+    #[test]
+    fn restores_from_spec() {
+        let spec: SortSpec = "-age,name,age".parse().unwrap();
+
+        let state = ColumnSortState::from(spec);
+
+        assert_eq!(state.spec().to_string(), "-age,name");
+    }
+}
@@ -0,0 +1,219 @@
+use alloc::sync::Arc;
+#[cfg(feature = "stats")]
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use crate::config::{self, Incomparable};
+#[cfg(feature = "stats")]
+use crate::stats::{self, SortStats};
+use crate::CompareFn;
+
+/// A comparator that [`SortBy`](crate::SortBy) sorts with.
+///
+/// Chaining sort levels nests the key comparators below into a single
+/// concrete type, so the whole chain is statically dispatched and can be
+/// inlined. [`SortBy::boxed`](crate::SortBy::boxed) erases it into a
+/// [`CompareFn`] when a nameable type is needed.
+pub trait Compare<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+
+    /// Compares like [`compare`](Compare::compare) while counting the keys it
+    /// extracts in `stats`.
+    #[cfg(feature = "stats")]
+    fn compare_counting(&self, a: &T, b: &T, _stats: &Cell<SortStats>) -> Ordering {
+        self.compare(a, b)
+    }
+
+    /// Erases the comparator into the boxed [`CompareFn`].
+    fn into_compare_fn<'a>(self) -> CompareFn<'a, T>
+    where
+        Self: Sized + Send + Sync + 'a,
+    {
+        Arc::new(move |a, b| self.compare(a, b))
+    }
+}
+
+impl<T, C> Compare<T> for &C
+where
+    C: Compare<T> + ?Sized,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (**self).compare(a, b)
+    }
+
+    #[cfg(feature = "stats")]
+    fn compare_counting(&self, a: &T, b: &T, stats: &Cell<SortStats>) -> Ordering {
+        (**self).compare_counting(a, b, stats)
+    }
+}
+
+impl<'b, T> Compare<T> for CompareFn<'b, T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+
+    fn into_compare_fn<'a>(self) -> CompareFn<'a, T>
+    where
+        Self: Sized + Send + Sync + 'a,
+    {
+        self
+    }
+}
+
+/// Orders by a key in ascending order.
+pub struct ByKey<F, V> {
+    key: F,
+    incomparable: Incomparable,
+    value: PhantomData<fn() -> V>,
+}
+
+/// Orders by a key in descending order.
+pub struct ByKeyDesc<F, V> {
+    key: F,
+    incomparable: Incomparable,
+    value: PhantomData<fn() -> V>,
+}
+
+/// Orders with a plain comparator function, see
+/// [`SortBy::map_compare`](crate::SortBy::map_compare).
+#[derive(Clone)]
+pub struct FnCompare<F>(pub F);
+
+/// Orders by `A`, breaking ties with `B`.
+#[derive(Clone)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<F, V> ByKey<F, V> {
+    pub(crate) fn new(key: F) -> ByKey<F, V> {
+        ByKey {
+            key,
+            incomparable: config::global_incomparable(),
+            value: PhantomData,
+        }
+    }
+}
+
+impl<F, V> ByKeyDesc<F, V> {
+    pub(crate) fn new(key: F) -> ByKeyDesc<F, V> {
+        ByKeyDesc {
+            key,
+            incomparable: config::global_incomparable(),
+            value: PhantomData,
+        }
+    }
+}
+
+impl<A, B> Then<A, B> {
+    pub(crate) fn new(first: A, second: B) -> Then<A, B> {
+        Then { first, second }
+    }
+}
+
+impl<T, F, V> Compare<T> for ByKey<F, V>
+where
+    V: PartialOrd,
+    F: Fn(&T) -> V,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        config::compare_keys(&(self.key)(a), &(self.key)(b), false, self.incomparable)
+    }
+
+    #[cfg(feature = "stats")]
+    fn compare_counting(&self, a: &T, b: &T, stats: &Cell<SortStats>) -> Ordering {
+        stats::count_keys(stats, 2);
+        self.compare(a, b)
+    }
+}
+
+impl<T, F, V> Compare<T> for ByKeyDesc<F, V>
+where
+    V: PartialOrd,
+    F: Fn(&T) -> V,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        config::compare_keys(&(self.key)(a), &(self.key)(b), true, self.incomparable)
+    }
+
+    #[cfg(feature = "stats")]
+    fn compare_counting(&self, a: &T, b: &T, stats: &Cell<SortStats>) -> Ordering {
+        stats::count_keys(stats, 2);
+        self.compare(a, b)
+    }
+}
+
+impl<T, F> Compare<T> for FnCompare<F>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
+/// An optional level, e.g. from
+/// [`SortBy::then_sort_by_if`](crate::SortBy::then_sort_by_if). `None`
+/// considers all items equal.
+impl<T, C> Compare<T> for Option<C>
+where
+    C: Compare<T>,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        match self {
+            Some(compare) => compare.compare(a, b),
+            None => Ordering::Equal,
+        }
+    }
+
+    #[cfg(feature = "stats")]
+    fn compare_counting(&self, a: &T, b: &T, stats: &Cell<SortStats>) -> Ordering {
+        match self {
+            Some(compare) => compare.compare_counting(a, b, stats),
+            None => Ordering::Equal,
+        }
+    }
+}
+
+impl<T, A, B> Compare<T> for Then<A, B>
+where
+    A: Compare<T>,
+    B: Compare<T>,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        match self.first.compare(a, b) {
+            Ordering::Equal => self.second.compare(a, b),
+            ordering => ordering,
+        }
+    }
+
+    #[cfg(feature = "stats")]
+    fn compare_counting(&self, a: &T, b: &T, stats: &Cell<SortStats>) -> Ordering {
+        match self.first.compare_counting(a, b, stats) {
+            Ordering::Equal => self.second.compare_counting(a, b, stats),
+            ordering => ordering,
+        }
+    }
+}
+
+impl<F: Clone, V> Clone for ByKey<F, V> {
+    fn clone(&self) -> Self {
+        ByKey {
+            key: self.key.clone(),
+            incomparable: self.incomparable,
+            value: PhantomData,
+        }
+    }
+}
+
+impl<F: Clone, V> Clone for ByKeyDesc<F, V> {
+    fn clone(&self) -> Self {
+        ByKeyDesc {
+            key: self.key.clone(),
+            incomparable: self.incomparable,
+            value: PhantomData,
+        }
+    }
+}
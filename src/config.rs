@@ -0,0 +1,453 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+#[cfg(feature = "rayon")]
+use crate::Parallelism;
+use crate::{trace, Overflow, SortError, Strategy};
+
+/// How a sort compares keys: lazily on every comparison, or once per item.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Algorithm {
+    /// Extracts the keys on every comparison, without extra memory.
+    #[default]
+    Comparison,
+    /// Extracts each key once up front and sorts the keys along with the
+    /// items, for expensive keys.
+    CachedKey,
+}
+
+/// What a sort does with keys that cannot be compared, like NaN. A key
+/// counts as incomparable when it is not comparable to itself, which also
+/// covers `Some(f64::NAN)` or tuples containing NaN.
+///
+/// `None` keys are comparable and come first in ascending order, like in
+/// `Option`'s own order. Wrap the comparator with
+/// [`cmp::nulls_last`](crate::cmp::nulls_last) to put them last.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Incomparable {
+    /// Treat them as equal to everything, like the plain `sort_by`. The
+    /// order is then not total and can come out wrong.
+    #[default]
+    Equal,
+    /// Put them before all other items, in input order.
+    First,
+    /// Put them after all other items, in input order.
+    Last,
+    /// Fail with [`SortError::IncomparableKeys`]. The plain `sort_by` calls
+    /// cannot fail and put them last instead, see
+    /// [`SortConfig::install_global`].
+    Error,
+}
+
+/// Everything about how a sort runs that is not the order itself, in one
+/// value that can be shared by many call sites, see
+/// [`SortByIteratorExt::sort_by_with`](crate::SortByIteratorExt::sort_by_with).
+///
+/// ```
+/// use sortby::*;
+///
+/// let config = SortConfig::new()
+///     .max_items(1_000, Overflow::Error)
+///     .incomparable(Incomparable::Last);
+///
+/// let sorted: Vec<f64> = vec![2.0, f64::NAN, 1.0]
+///     .into_iter()
+///     .sort_by_with(|v| *v, &config)
+///     .unwrap()
+///     .collect();
+/// assert_eq!(sorted[..2], [1.0, 2.0]);
+/// assert!(sorted[2].is_nan());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SortConfig<'p> {
+    strategy: Strategy,
+    algorithm: Algorithm,
+    #[cfg(feature = "rayon")]
+    parallel_above: Option<usize>,
+    #[cfg(feature = "rayon")]
+    parallelism: Parallelism<'p>,
+    max_items: Option<(usize, Overflow)>,
+    incomparable: Incomparable,
+    lifetime: PhantomData<&'p ()>,
+}
+
+impl<'p> SortConfig<'p> {
+    /// A stable comparison sort on the calling thread, without a limit,
+    /// treating incomparable keys as equal.
+    pub fn new() -> SortConfig<'p> {
+        SortConfig::default()
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> SortConfig<'p> {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Shorthand for `strategy(Strategy::Unstable)`.
+    pub fn unstable(self) -> SortConfig<'p> {
+        self.strategy(Strategy::Unstable)
+    }
+
+    pub fn algorithm(mut self, algorithm: Algorithm) -> SortConfig<'p> {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sorts in parallel when there are more than `n` items, on the pool
+    /// [`parallelism`](SortConfig::parallelism) says. The result is the same
+    /// as a serial sort. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn parallel_above(mut self, n: usize) -> SortConfig<'p> {
+        self.parallel_above = Some(n);
+        self
+    }
+
+    /// Where the sorts above [`parallel_above`](SortConfig::parallel_above)
+    /// run, rayon's global pool by default. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn parallelism(mut self, parallelism: Parallelism<'p>) -> SortConfig<'p> {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Reads at most `n` items, plus one to detect an overflow, which is then
+    /// handled according to `overflow`, like [`SortBy::max_items`].
+    ///
+    /// [`SortBy::max_items`]: crate::SortBy::max_items
+    pub fn max_items(mut self, n: usize, overflow: Overflow) -> SortConfig<'p> {
+        self.max_items = Some((n, overflow));
+        self
+    }
+
+    pub fn incomparable(mut self, incomparable: Incomparable) -> SortConfig<'p> {
+        self.incomparable = incomparable;
+        self
+    }
+
+    /// The first option that lazy sorts cannot apply, if any is set.
+    #[cfg(feature = "std")]
+    fn unsupported_global_option(&self) -> Option<&'static str> {
+        #[cfg(feature = "rayon")]
+        if self.parallel_above.is_some() {
+            return Some("parallel_above");
+        }
+        #[cfg(feature = "rayon")]
+        if self.parallelism != Parallelism::Global {
+            return Some("parallelism");
+        }
+        if self.strategy != Strategy::Stable {
+            Some("strategy")
+        } else if self.algorithm != Algorithm::Comparison {
+            Some("algorithm")
+        } else if self.max_items.is_some() {
+            Some("max_items")
+        } else {
+            None
+        }
+    }
+}
+
+impl SortConfig<'static> {
+    /// Installs the process-wide default that the plain `sort_by` calls
+    /// consult, e.g. for a platform team curating defaults for a large code
+    /// base. Requires the `std` feature.
+    ///
+    /// Lazy sorts like [`SortByIteratorExt::sort_by`] pick up the
+    /// [`incomparable`](SortConfig::incomparable) policy when they are
+    /// created. As they cannot fail, they put incomparable keys last under
+    /// [`Incomparable::Error`] instead of panicking, as only
+    /// [`sort_by_with`] can report those keys as an error.
+    ///
+    /// A config that sets any other option is rejected with
+    /// [`UnsupportedGlobalOption`] rather than silently ignored, as a lazy
+    /// sort cannot honor it: it is always stable, its items need not be
+    /// `Send` for a parallel sort, it has no error channel for a limit, and
+    /// it composes its levels from arbitrary comparators rather than one key.
+    /// Pass those options to [`sort_by_with`] per call.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// SortConfig::new()
+    ///     .incomparable(Incomparable::Last)
+    ///     .install_global()
+    ///     .unwrap();
+    ///
+    /// let sorted: Vec<f64> = vec![f64::NAN, 2.0, 1.0].into_iter().sort_by(|v| *v).collect();
+    /// assert_eq!(sorted[..2], [1.0, 2.0]);
+    ///
+    /// SortConfig::reset_global();
+    /// assert_eq!(SortConfig::global(), SortConfig::new());
+    /// ```
+    ///
+    /// [`SortByIteratorExt::sort_by`]: crate::SortByIteratorExt::sort_by
+    /// [`sort_by_with`]: crate::SortByIteratorExt::sort_by_with
+    #[cfg(feature = "std")]
+    pub fn install_global(self) -> Result<(), UnsupportedGlobalOption> {
+        if let Some(option) = self.unsupported_global_option() {
+            return Err(UnsupportedGlobalOption { option });
+        }
+        *global::CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(self);
+        global::INSTALLED.store(true, core::sync::atomic::Ordering::Release);
+        Ok(())
+    }
+
+    /// Restores the built-in default, see
+    /// [`install_global`](SortConfig::install_global). Requires the `std`
+    /// feature.
+    #[cfg(feature = "std")]
+    pub fn reset_global() {
+        global::INSTALLED.store(false, core::sync::atomic::Ordering::Release);
+        *global::CONFIG.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// The installed process-wide default, or [`SortConfig::new`] if none was
+    /// installed.
+    pub fn global() -> SortConfig<'static> {
+        with_global(SortConfig::clone)
+    }
+}
+
+#[cfg(feature = "std")]
+mod global {
+    use core::sync::atomic::AtomicBool;
+    use std::sync::RwLock;
+
+    use super::SortConfig;
+
+    pub(super) static CONFIG: RwLock<Option<SortConfig<'static>>> = RwLock::new(None);
+    /// Set while a config is installed, so sorts skip the lock otherwise.
+    pub(super) static INSTALLED: AtomicBool = AtomicBool::new(false);
+}
+
+/// Calls `f` with the process-wide default config.
+pub(crate) fn with_global<R>(f: impl FnOnce(&SortConfig<'static>) -> R) -> R {
+    #[cfg(feature = "std")]
+    if global::INSTALLED.load(core::sync::atomic::Ordering::Acquire) {
+        if let Some(config) = &*global::CONFIG.read().unwrap_or_else(|e| e.into_inner()) {
+            return f(config);
+        }
+    }
+    f(&SortConfig::default())
+}
+
+/// The incomparable policy of the process-wide default config for lazy
+/// sorts, which cannot report [`Incomparable::Error`].
+pub(crate) fn global_incomparable() -> Incomparable {
+    match with_global(|config| config.incomparable) {
+        Incomparable::Error => Incomparable::Last,
+        incomparable => incomparable,
+    }
+}
+
+/// Returned by [`SortConfig::install_global`] for a config that sets an
+/// option the plain `sort_by` calls cannot apply.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnsupportedGlobalOption {
+    /// The name of the option, e.g. `"parallel_above"`.
+    pub option: &'static str,
+}
+
+impl core::fmt::Display for UnsupportedGlobalOption {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "`{}` cannot be installed globally, pass it to `sort_by_with` instead",
+            self.option
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsupportedGlobalOption {}
+
+/// Collects and sorts `items` by `key` as `config` says.
+pub(crate) fn sort_with<T, V, F>(
+    items: impl Iterator<Item = T>,
+    key: F,
+    descending: bool,
+    config: &SortConfig<'_>,
+) -> Result<Vec<T>, SortError>
+where
+    T: Send,
+    V: PartialOrd + Send,
+    F: Fn(&T) -> V + Sync,
+{
+    let mut items = collect(items, config)?;
+    if config.incomparable == Incomparable::Error {
+        if let Some(index) = items.iter().position(|item| is_incomparable(&key(item))) {
+            return Err(SortError::IncomparableKeys { index });
+        }
+    }
+
+    let _span = trace::sort(&items);
+    let policy = config.incomparable;
+    match config.algorithm {
+        Algorithm::Comparison => sort_slice(
+            &mut items,
+            |a, b| compare_keys(&key(a), &key(b), descending, policy),
+            config,
+        ),
+        Algorithm::CachedKey => {
+            let mut keyed: Vec<(V, T)> = items.into_iter().map(|item| (key(&item), item)).collect();
+            sort_slice(
+                &mut keyed,
+                |a, b| compare_keys(&a.0, &b.0, descending, policy),
+                config,
+            );
+            items = keyed.into_iter().map(|(_, item)| item).collect();
+        }
+    }
+    Ok(items)
+}
+
+/// Collects `items`, enforcing the item limit of `config`.
+fn collect<T>(
+    items: impl Iterator<Item = T>,
+    config: &SortConfig<'_>,
+) -> Result<Vec<T>, SortError> {
+    let mut span = trace::collect::<T>();
+    let items = match config.max_items {
+        Some((n, overflow)) => {
+            let mut items: Vec<T> = items.take(n.saturating_add(1)).collect();
+            if items.len() > n {
+                match overflow {
+                    Overflow::Error => return Err(SortError::LimitExceeded { limit: n }),
+                    Overflow::Truncate => items.truncate(n),
+                }
+            }
+            items
+        }
+        None => items.collect(),
+    };
+    span.record_items(items.len());
+    Ok(items)
+}
+
+fn sort_slice<T, C>(items: &mut [T], compare: C, config: &SortConfig<'_>)
+where
+    T: Send,
+    C: Fn(&T, &T) -> Ordering + Sync,
+{
+    #[cfg(feature = "rayon")]
+    if config.parallel_above.is_some_and(|n| items.len() > n) {
+        config.parallelism.sort_by(items, config.strategy, compare);
+        return;
+    }
+    match config.strategy {
+        Strategy::Stable => items.sort_by(compare),
+        Strategy::Unstable => items.sort_unstable_by(compare),
+    }
+}
+
+fn is_incomparable<V: PartialOrd>(key: &V) -> bool {
+    key.partial_cmp(key).is_none()
+}
+
+/// Compares two keys under `policy`. [`Incomparable::Error`] panics on an
+/// incomparable key; [`sort_with`] checks for those up front.
+pub(crate) fn compare_keys<V: PartialOrd>(
+    a: &V,
+    b: &V,
+    descending: bool,
+    policy: Incomparable,
+) -> Ordering {
+    if policy == Incomparable::Error && (is_incomparable(a) || is_incomparable(b)) {
+        panic!("incomparable sort key");
+    }
+    if let Incomparable::First | Incomparable::Last = policy {
+        let first = policy == Incomparable::First;
+        match (is_incomparable(a), is_incomparable(b)) {
+            (false, false) => {}
+            (true, true) => return Ordering::Equal,
+            (true, false) if first => return Ordering::Less,
+            (false, true) if first => return Ordering::Greater,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+        }
+    }
+    let ordering = a.partial_cmp(b).unwrap_or(Ordering::Equal);
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    fn sort(input: &[(f64, u8)], config: &SortConfig<'_>) -> Result<Vec<u8>, SortError> {
+        let sorted = input.iter().sort_by_desc_with(|v| v.0, config)?;
+        Ok(sorted.map(|v| v.1).collect())
+    }
+
+    #[test]
+    fn applies_every_option() {
+        let input = [(1.0, 0), (f64::NAN, 1), (3.0, 2), (1.0, 3), (f64::NAN, 4)];
+
+        let first = SortConfig::new().incomparable(Incomparable::First);
+        assert_eq!(sort(&input, &first), Ok(vec![1, 4, 2, 0, 3]));
+
+        let last = first.clone().incomparable(Incomparable::Last);
+        assert_eq!(sort(&input, &last), Ok(vec![2, 0, 3, 1, 4]));
+        let cached = last.clone().algorithm(Algorithm::CachedKey);
+        assert_eq!(sort(&input, &cached), Ok(vec![2, 0, 3, 1, 4]));
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            sort(&input, &last.clone().parallel_above(2)),
+            Ok(vec![2, 0, 3, 1, 4])
+        );
+
+        let strict = SortConfig::new().incomparable(Incomparable::Error);
+        assert_eq!(
+            sort(&input, &strict),
+            Err(SortError::IncomparableKeys { index: 1 })
+        );
+
+        let limited = last.max_items(4, Overflow::Error);
+        assert_eq!(
+            sort(&input, &limited),
+            Err(SortError::LimitExceeded { limit: 4 })
+        );
+        let truncated = limited.max_items(3, Overflow::Truncate);
+        assert_eq!(sort(&input, &truncated), Ok(vec![2, 0, 1]));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn sorts_in_parallel_on_the_given_pool() {
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name(|i| format!("sort-{}", i))
+            .build()
+            .unwrap();
+        let config = SortConfig::new()
+            .parallel_above(10)
+            .parallelism(Parallelism::Pool(&pool));
+        let off_pool = AtomicBool::new(false);
+
+        let sorted: Vec<_> = (0..1000u32)
+            .rev()
+            .sort_by_with(
+                |v| {
+                    let name = std::thread::current().name().map(String::from);
+                    if !name.is_some_and(|name| name.starts_with("sort-")) {
+                        off_pool.store(true, Ordering::Relaxed);
+                    }
+                    *v
+                },
+                &config,
+            )
+            .unwrap()
+            .collect();
+
+        assert!(sorted.windows(2).all(|w| w[0] < w[1]));
+        assert!(!off_pool.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,87 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Number of items [`check`] samples; all of their pairs and triples are
+/// compared.
+const SAMPLE: usize = 32;
+
+/// Panics if `compare` is not antisymmetric or not transitive on a sample of
+/// `items`, naming the input positions of the offending items, see
+/// [`SortBy::check_consistency`](crate::SortBy::check_consistency).
+pub(crate) fn check<T, F>(items: &[T], compare: F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let step = (items.len() / SAMPLE).max(1);
+    let sample: Vec<usize> = (0..items.len()).step_by(step).take(SAMPLE).collect();
+
+    for &a in &sample {
+        for &b in &sample {
+            if compare(&items[a], &items[b]) != compare(&items[b], &items[a]).reverse() {
+                panic!(
+                    "comparator is not antisymmetric for the items at input positions {} and {}",
+                    a, b
+                );
+            }
+        }
+    }
+
+    for &a in &sample {
+        for &b in &sample {
+            let ab = compare(&items[a], &items[b]);
+            if ab == Ordering::Greater {
+                continue;
+            }
+            for &c in &sample {
+                let bc = compare(&items[b], &items[c]);
+                if bc == Ordering::Greater {
+                    continue;
+                }
+                let ac = compare(&items[a], &items[c]);
+                let transitive = match (ab, bc) {
+                    (Ordering::Equal, Ordering::Equal) => ac == Ordering::Equal,
+                    _ => ac == Ordering::Less,
+                };
+                if !transitive {
+                    panic!(
+                        "comparator is not transitive for the items at input positions {}, {} and {}",
+                        a, b, c
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn accepts_total_orders() {
+        let input = vec![3.0, 1.0, 2.0, 1.0];
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by(|v| *v)
+            .check_consistency()
+            .collect();
+
+        assert_eq!(actual, vec![1.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(
+        expected = "comparator is not transitive for the items at input positions 0, 1 and 2"
+    )]
+    fn reports_nan_keys() {
+        let input = vec![1.0, f64::NAN, 2.0];
+
+        input
+            .into_iter()
+            .sort_by(|v| *v)
+            .check_consistency()
+            .for_each(drop);
+    }
+}
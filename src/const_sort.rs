@@ -0,0 +1,84 @@
+//! Sorts arrays of primitives during const evaluation, so sorted lookup
+//! tables can be embedded as `static`s without a build script.
+//!
+//! ```
+//! use sortby::const_sort;
+//!
+//! static PRIMES: [u32; 5] = const_sort::sort_u32([7, 2, 11, 5, 3]);
+//! assert_eq!(PRIMES, [2, 3, 5, 7, 11]);
+//! assert!(PRIMES.binary_search(&5).is_ok());
+//! ```
+//!
+//! Trait methods cannot be called in a `const fn`, so there is one function
+//! per type. They use an insertion sort, which is quadratic but only costs
+//! compile time.
+
+macro_rules! const_sorts {
+    ($($name:ident, $name_desc:ident: $t:ty;)*) => {
+        $(
+            #[doc = concat!("Sorts an array of `", stringify!($t), "` in ascending order.")]
+            pub const fn $name<const N: usize>(mut items: [$t; N]) -> [$t; N] {
+                let mut i = 1;
+                while i < N {
+                    let mut j = i;
+                    while j > 0 && items[j - 1] > items[j] {
+                        let item = items[j];
+                        items[j] = items[j - 1];
+                        items[j - 1] = item;
+                        j -= 1;
+                    }
+                    i += 1;
+                }
+                items
+            }
+
+            #[doc = concat!("Sorts an array of `", stringify!($t), "` in descending order.")]
+            pub const fn $name_desc<const N: usize>(mut items: [$t; N]) -> [$t; N] {
+                let mut i = 1;
+                while i < N {
+                    let mut j = i;
+                    while j > 0 && items[j - 1] < items[j] {
+                        let item = items[j];
+                        items[j] = items[j - 1];
+                        items[j - 1] = item;
+                        j -= 1;
+                    }
+                    i += 1;
+                }
+                items
+            }
+        )*
+    };
+}
+
+const_sorts! {
+    sort_u8, sort_u8_desc: u8;
+    sort_u16, sort_u16_desc: u16;
+    sort_u32, sort_u32_desc: u32;
+    sort_u64, sort_u64_desc: u64;
+    sort_u128, sort_u128_desc: u128;
+    sort_usize, sort_usize_desc: usize;
+    sort_i8, sort_i8_desc: i8;
+    sort_i16, sort_i16_desc: i16;
+    sort_i32, sort_i32_desc: i32;
+    sort_i64, sort_i64_desc: i64;
+    sort_i128, sort_i128_desc: i128;
+    sort_isize, sort_isize_desc: isize;
+    sort_char, sort_char_desc: char;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASC: [i32; 6] = sort_i32([3, -1, 4, 1, -5, 9]);
+    const DESC: [char; 4] = sort_char_desc(['b', 'd', 'a', 'c']);
+    const EMPTY: [u8; 0] = sort_u8([]);
+
+    #[test]
+    fn sorts_at_compile_time() {
+        assert_eq!(ASC, [-5, -1, 1, 3, 4, 9]);
+        assert_eq!(DESC, ['d', 'c', 'b', 'a']);
+        assert_eq!(EMPTY, [0u8; 0]);
+    }
+}
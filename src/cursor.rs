@@ -0,0 +1,138 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{Compare, SortBy, SortError};
+
+/// Encodes the sort keys of a page's last item into an opaque, URL-safe
+/// cursor for keyset pagination. Requires the `cursor` feature.
+///
+/// ```
+/// use sortby::*;
+///
+/// let items = vec![(3, "c"), (1, "a"), (2, "b")];
+/// let key = |v: &(i32, &str)| (v.0, v.1.to_string());
+///
+/// let page: Vec<_> = items.clone().into_iter().sort_by(key).take(2).collect();
+/// let cursor = encode_cursor(&key(page.last().unwrap())).unwrap();
+///
+/// let next: Vec<_> = items
+///     .into_iter()
+///     .sort_by(key)
+///     .skip_after_cursor(&cursor, key)
+///     .unwrap()
+///     .collect();
+/// assert_eq!(next, vec![(3, "c")]);
+/// ```
+///
+/// Fails if the keys cannot be serialized to JSON, e.g. a map with non-string
+/// keys.
+pub fn encode_cursor<K: Serialize>(key: &K) -> Result<String, UnencodableKey> {
+    let json = serde_json::to_vec(key).map_err(|e| UnencodableKey {
+        message: e.to_string(),
+    })?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+}
+
+/// Decodes a cursor made by [`encode_cursor`] back into the sort keys.
+pub fn decode_cursor<K: DeserializeOwned>(cursor: &str) -> Result<K, InvalidCursor> {
+    let json: Vec<u8> = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| InvalidCursor)?;
+    serde_json::from_slice(&json).map_err(|_| InvalidCursor)
+}
+
+impl<'a, I, C> SortBy<'a, I, C>
+where
+    I: Iterator,
+    C: Compare<I::Item>,
+{
+    /// Continues after the item a cursor from [`encode_cursor`] points to,
+    /// see [`skip_after_key`](SortBy::skip_after_key). Fails with
+    /// [`SortError::InvalidCursor`] if the cursor does not decode to `K`.
+    pub fn skip_after_cursor<K, F>(
+        self,
+        cursor: &str,
+        key: F,
+    ) -> Result<alloc::vec::IntoIter<I::Item>, SortError>
+    where
+        K: PartialOrd + DeserializeOwned,
+        F: Fn(&I::Item) -> K,
+    {
+        let boundary: K = decode_cursor(cursor)?;
+        Ok(self.skip_after_key(&boundary, key))
+    }
+}
+
+/// Returned when a pagination cursor was not made by [`encode_cursor`] for
+/// the expected key type, e.g. because a client tampered with it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidCursor;
+
+impl core::fmt::Display for InvalidCursor {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid pagination cursor")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCursor {}
+
+/// Returned by [`encode_cursor`] when the sort keys cannot be serialized.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnencodableKey {
+    /// What the serializer reported.
+    pub message: String,
+}
+
+impl core::fmt::Display for UnencodableKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "cannot encode sort keys: {}", self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnencodableKey {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn round_trips_keys() {
+        let cursor = encode_cursor(&(42u32, "bob")).unwrap();
+
+        assert!(cursor
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(
+            decode_cursor::<(u32, String)>(&cursor),
+            Ok((42, "bob".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_cursors() {
+        let cursor = encode_cursor(&"bob").unwrap();
+
+        assert_eq!(decode_cursor::<u32>(&cursor), Err(InvalidCursor));
+        assert_eq!(decode_cursor::<u32>("not a cursor!"), Err(InvalidCursor));
+
+        let actual = vec![1, 2, 3]
+            .into_iter()
+            .sort_by(|v| *v)
+            .skip_after_cursor(&cursor, |v| *v);
+        assert_eq!(actual.err(), Some(SortError::InvalidCursor));
+    }
+
+    #[test]
+    fn reports_unencodable_keys() {
+        let mut key = alloc::collections::BTreeMap::new();
+        key.insert((1, 2), "a");
+
+        assert!(encode_cursor(&key).is_err());
+    }
+}
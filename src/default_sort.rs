@@ -0,0 +1,40 @@
+use core::cmp::Ordering;
+
+/// The canonical ordering of a type, defined once next to the type so every
+/// call site sorts it the same way with
+/// [`SortByIteratorExt::sort_default`](crate::SortByIteratorExt::sort_default).
+///
+/// With the `derive` feature, `#[derive(DefaultSort)]` builds it from the
+/// fields marked with `#[sort]`, in field order:
+///
+/// - `#[sort(desc)]` sorts the field in descending order,
+/// - `#[sort(by = "len")]` sorts by the result of the field's `len()` method,
+/// - `#[sort(level = 0)]` moves the field to that level; fields without one
+///   come after all fields with one.
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use sortby::*;
+///
+/// #[derive(DefaultSort, Debug, PartialEq)]
+/// struct Person {
+///     #[sort(by = "len", level = 1)]
+///     name: &'static str,
+///     #[sort(desc, level = 0)]
+///     age: u32,
+/// }
+///
+/// let people = vec![
+///     Person { name: "Ann", age: 30 },
+///     Person { name: "Bob", age: 40 },
+///     Person { name: "Jo", age: 30 },
+/// ];
+/// let names: Vec<_> = people.into_iter().sort_default().map(|p| p.name).collect();
+/// assert_eq!(names, vec!["Bob", "Jo", "Ann"]);
+/// # }
+/// ```
+pub trait DefaultSort {
+    /// Compares `a` and `b` by the type's default sort levels.
+    fn default_sort(a: &Self, b: &Self) -> Ordering;
+}
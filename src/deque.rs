@@ -0,0 +1,56 @@
+use alloc::collections::VecDeque;
+
+use crate::{compare_by, compare_by_desc, SliceSortBy};
+
+/// Chained in-place sorting for a `VecDeque`.
+///
+/// The ring buffer is rotated into a single contiguous slice first (see
+/// [`VecDeque::make_contiguous`]), which is then sorted without copying the
+/// items out.
+pub trait VecDequeSortByExt<T> {
+    fn sort_by_key_chain<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a;
+
+    fn sort_by_key_chain_desc<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a;
+}
+
+impl<T> VecDequeSortByExt<T> for VecDeque<T> {
+    fn sort_by_key_chain<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        SliceSortBy::new(self.make_contiguous(), compare_by(f))
+    }
+
+    fn sort_by_key_chain_desc<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        SliceSortBy::new(self.make_contiguous(), compare_by_desc(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_wrapped_deque_in_place() {
+        let mut queue = VecDeque::with_capacity(4);
+        queue.extend(vec![3, 9, 1]);
+        queue.pop_front();
+        queue.push_back(7);
+        queue.push_back(4);
+
+        queue.sort_by_key_chain_desc(|v| *v).sort();
+
+        assert_eq!(queue, vec![9, 7, 4, 1]);
+    }
+}
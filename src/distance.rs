@@ -0,0 +1,140 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// How much two orderings of the same items differ, see [`ranking_distance`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RankingDistance {
+    /// Number of items found in both orderings, which all other fields are
+    /// computed over.
+    pub common: usize,
+    /// Number of pairs the two orderings put in opposite order, also known as
+    /// the Kendall tau distance.
+    pub discordant_pairs: usize,
+    /// Kendall's tau rank correlation: 1 for identical orderings, -1 for
+    /// reversed ones.
+    pub kendall_tau: f64,
+    /// Sum of the absolute differences of each item's ranks, Spearman's
+    /// footrule.
+    pub spearman_footrule: usize,
+}
+
+/// Compares two orderings of the same items, e.g. the results of two ranking
+/// functions in an A/B test. `id` identifies an item across both orderings;
+/// items found in only one of them are ignored.
+///
+/// Runs in `O(n log n)`.
+///
+/// ```
+/// use sortby::ranking_distance;
+///
+/// let a = ["x", "y", "z"];
+/// let b = ["y", "x", "z"];
+/// let distance = ranking_distance(&a, &b, |v| *v);
+///
+/// assert_eq!(distance.discordant_pairs, 1);
+/// assert_eq!(distance.spearman_footrule, 2);
+/// assert!((distance.kendall_tau - 1.0 / 3.0).abs() < 1e-9);
+/// ```
+pub fn ranking_distance<T, K, F>(a: &[T], b: &[T], id: F) -> RankingDistance
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    let ranks_b: BTreeMap<K, usize> = b
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (id(item), i))
+        .collect();
+    let in_b: Vec<usize> = a
+        .iter()
+        .filter_map(|item| ranks_b.get(&id(item)).copied())
+        .collect();
+
+    // ranks in `b` among the common items, listed in the order of `a`
+    let mut order: Vec<usize> = (0..in_b.len()).collect();
+    order.sort_unstable_by_key(|&i| in_b[i]);
+    let mut ranks = alloc::vec![0; in_b.len()];
+    for (rank, &i) in order.iter().enumerate() {
+        ranks[i] = rank;
+    }
+
+    let spearman_footrule = ranks
+        .iter()
+        .enumerate()
+        .map(|(i, rank)| i.abs_diff(*rank))
+        .sum();
+    let discordant_pairs = count_inversions(&mut ranks);
+    let common = in_b.len();
+    let pairs = common * common.saturating_sub(1) / 2;
+    let kendall_tau = if pairs == 0 {
+        1.0
+    } else {
+        1.0 - 2.0 * discordant_pairs as f64 / pairs as f64
+    };
+
+    RankingDistance {
+        common,
+        discordant_pairs,
+        kendall_tau,
+        spearman_footrule,
+    }
+}
+
+/// Counts the pairs `i < j` with `items[i] > items[j]` with a merge sort,
+/// leaving `items` sorted.
+fn count_inversions(items: &mut [usize]) -> usize {
+    if items.len() < 2 {
+        return 0;
+    }
+    let mid = items.len() / 2;
+    let mut inversions = count_inversions(&mut items[..mid]) + count_inversions(&mut items[mid..]);
+
+    let mut merged = Vec::with_capacity(items.len());
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < items.len() {
+        if items[i] <= items[j] {
+            merged.push(items[i]);
+            i += 1;
+        } else {
+            inversions += mid - i;
+            merged.push(items[j]);
+            j += 1;
+        }
+    }
+    merged.extend_from_slice(&items[i..mid]);
+    merged.extend_from_slice(&items[j..]);
+    items.copy_from_slice(&merged);
+    inversions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ranking_distance;
+
+    #[test]
+    fn measures_identical_and_reversed_orderings() {
+        let a = [1, 2, 3, 4, 5];
+        let reversed = [5, 4, 3, 2, 1];
+
+        let same = ranking_distance(&a, &a, |v| *v);
+        assert_eq!((same.discordant_pairs, same.spearman_footrule), (0, 0));
+        assert_eq!(same.kendall_tau, 1.0);
+
+        let opposite = ranking_distance(&a, &reversed, |v| *v);
+        assert_eq!(opposite.discordant_pairs, 10);
+        assert_eq!(opposite.spearman_footrule, 12);
+        assert_eq!(opposite.kendall_tau, -1.0);
+    }
+
+    #[test]
+    fn ignores_items_missing_from_one_ordering() {
+        let a = [(1, 'a'), (2, 'b'), (3, 'c'), (9, 'x')];
+        let b = [(3, 'c'), (7, 'y'), (1, 'a'), (2, 'b')];
+
+        let distance = ranking_distance(&a, &b, |v| v.0);
+
+        assert_eq!(distance.common, 3);
+        assert_eq!(distance.discordant_pairs, 2);
+        assert_eq!(distance.spearman_footrule, 4);
+    }
+}
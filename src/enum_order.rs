@@ -0,0 +1,203 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::mem::Discriminant;
+
+use crate::Compare;
+
+/// An explicit order of an enum's variants, independent of the order they
+/// are declared in, e.g. a per-screen display order.
+///
+/// Variants are told apart by their discriminant only, so a data-carrying
+/// variant can be listed with any payload. Variants that are not listed sort
+/// after all listed ones.
+///
+/// ```
+/// use sortby::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Status {
+///     Open,
+///     Closed,
+///     Blocked(&'static str),
+/// }
+///
+/// let order = EnumOrder::new(&[Status::Blocked(""), Status::Open]);
+/// let sorted: Vec<_> = vec![Status::Closed, Status::Open, Status::Blocked("ci")]
+///     .into_iter()
+///     .sort_by_enum_order(|v| v, &order)
+///     .collect();
+/// assert_eq!(sorted, vec![Status::Blocked("ci"), Status::Open, Status::Closed]);
+/// ```
+///
+/// Orders can also be declared on the enum itself, see [`VariantOrder`].
+#[derive(Clone, Debug)]
+pub struct EnumOrder<E> {
+    ranks: Ranks<E>,
+}
+
+#[derive(Clone, Debug)]
+enum Ranks<E> {
+    Listed(Vec<Discriminant<E>>),
+    Declared(fn(&E) -> usize),
+}
+
+impl<E> EnumOrder<E> {
+    /// Ranks the variants of `variants` in the given order.
+    pub fn new(variants: &[E]) -> EnumOrder<E> {
+        EnumOrder {
+            ranks: Ranks::Listed(variants.iter().map(core::mem::discriminant).collect()),
+        }
+    }
+
+    /// Returns the position of `value`'s variant, or the number of listed
+    /// variants if it is not listed. For a declared order, returns the
+    /// declared rank, or `usize::MAX` for variants without one.
+    pub fn rank(&self, value: &E) -> usize {
+        match self.ranks {
+            Ranks::Listed(ref variants) => {
+                let variant = core::mem::discriminant(value);
+                variants
+                    .iter()
+                    .position(|v| *v == variant)
+                    .unwrap_or(variants.len())
+            }
+            Ranks::Declared(rank) => rank(value),
+        }
+    }
+}
+
+impl<E: VariantOrder> EnumOrder<E> {
+    /// The default order declared on the enum.
+    pub fn declared() -> EnumOrder<E> {
+        EnumOrder {
+            ranks: Ranks::Declared(E::variant_rank),
+        }
+    }
+
+    /// The order called `name` declared on the enum, or `None` if the enum
+    /// declares no such order.
+    pub fn named(name: &str) -> Option<EnumOrder<E>> {
+        E::named_variant_ranks(name).map(|rank| EnumOrder {
+            ranks: Ranks::Declared(rank),
+        })
+    }
+}
+
+/// Variant orders declared next to an enum, so every screen that sorts it
+/// picks its order by name instead of listing the variants again, see
+/// [`EnumOrder::declared`] and [`EnumOrder::named`].
+///
+/// With the `derive` feature, `#[derive(EnumOrder)]` builds it from
+/// `#[order(...)]` variant attributes:
+///
+/// - `#[order(rank = 0)]` ranks the variant in the default order,
+/// - `#[order(name = "triage", rank = 0)]` ranks it in the order called
+///   `triage`; a variant can have one attribute per order.
+///
+/// Lower ranks sort first and equal ranks tie. Variants without a rank in an
+/// order sort after all variants with one.
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use sortby::*;
+///
+/// #[derive(EnumOrder, Debug, PartialEq)]
+/// enum Status {
+///     #[order(rank = 1)]
+///     #[order(name = "triage", rank = 0)]
+///     Open,
+///     #[order(rank = 0)]
+///     Blocked(&'static str),
+///     Closed,
+/// }
+///
+/// let input = || vec![Status::Closed, Status::Open, Status::Blocked("ci")];
+///
+/// let order = EnumOrder::declared();
+/// let sorted: Vec<_> = input().into_iter().sort_by_enum_order(|v| v, &order).collect();
+/// assert_eq!(sorted, vec![Status::Blocked("ci"), Status::Open, Status::Closed]);
+///
+/// let triage = EnumOrder::named("triage").unwrap();
+/// let sorted: Vec<_> = input().into_iter().sort_by_enum_order(|v| v, &triage).collect();
+/// assert_eq!(sorted, vec![Status::Open, Status::Closed, Status::Blocked("ci")]);
+/// # }
+/// ```
+pub trait VariantOrder: Sized {
+    /// Returns the rank of the variant of `value` in the default order.
+    fn variant_rank(value: &Self) -> usize;
+
+    /// Returns how the order called `name` ranks the variants, or `None` if
+    /// there is no such order.
+    fn named_variant_ranks(name: &str) -> Option<fn(&Self) -> usize>;
+}
+
+/// Orders by the rank of an enum key in an [`EnumOrder`], see
+/// [`SortByIteratorExt::sort_by_enum_order`](crate::SortByIteratorExt::sort_by_enum_order).
+pub struct ByEnumOrder<'o, F, E> {
+    key: F,
+    order: &'o EnumOrder<E>,
+}
+
+impl<'o, F, E> ByEnumOrder<'o, F, E> {
+    pub(crate) fn new(key: F, order: &'o EnumOrder<E>) -> ByEnumOrder<'o, F, E> {
+        ByEnumOrder { key, order }
+    }
+}
+
+impl<'o, T, F, E> Compare<T> for ByEnumOrder<'o, F, E>
+where
+    F: Fn(&T) -> &E,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.order
+            .rank((self.key)(a))
+            .cmp(&self.order.rank((self.key)(b)))
+    }
+}
+
+impl<'o, F: Clone, E> Clone for ByEnumOrder<'o, F, E> {
+    fn clone(&self) -> Self {
+        ByEnumOrder::new(self.key.clone(), self.order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnumOrder;
+    use crate::SortByIteratorExt;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Level {
+        Low,
+        Medium,
+        High,
+    }
+
+    #[test]
+    fn orders_by_explicit_variant_ranks() {
+        let input = vec![
+            (Level::Low, 1),
+            (Level::High, 2),
+            (Level::Medium, 3),
+            (Level::High, 4),
+        ];
+        let order = EnumOrder::new(&[Level::High, Level::Low]);
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by_enum_order(|v| &v.0, &order)
+            .then_sort_by_desc(|v| v.1)
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                (Level::High, 4),
+                (Level::High, 2),
+                (Level::Low, 1),
+                (Level::Medium, 3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use alloc::collections::TryReserveError;
+use alloc::string::String;
+
+#[cfg(feature = "json")]
+use crate::json::MixedTypes;
+#[cfg(feature = "cursor")]
+use crate::InvalidCursor;
+use crate::UnknownField;
+
+/// The error of every fallible sort, e.g. [`SortBy::try_into_vec`] and
+/// [`SortByIteratorExt::sort_by_with`].
+///
+/// Most variants are produced only by a few sorts, e.g.
+/// `KeyExtractionFailed` by key extractors that can fail and
+/// `IncompatibleOrder` by merges of sorted inputs. Variants of optional
+/// features only exist with the feature.
+///
+/// [`SortBy::try_into_vec`]: crate::SortBy::try_into_vec
+/// [`SortByIteratorExt::sort_by_with`]: crate::SortByIteratorExt::sort_by_with
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SortError {
+    /// A key extractor or comparator panicked. All items were dropped
+    /// exactly once.
+    ComparatorPanicked {
+        /// The panic message, if it was a string.
+        message: Option<String>,
+    },
+    /// The key of the item at input position `index` cannot be compared,
+    /// e.g. NaN, and the sort was told to reject those, see
+    /// [`Incomparable::Error`](crate::Incomparable::Error).
+    IncomparableKeys { index: usize },
+    /// The key of the item at input position `index` could not be extracted.
+    KeyExtractionFailed {
+        index: usize,
+        /// What the extractor reported.
+        message: String,
+    },
+    /// A buffer could not be allocated.
+    AllocationFailed(TryReserveError),
+    /// The source yielded more items than allowed.
+    LimitExceeded { limit: usize },
+    /// The source yielded another number of items than a fixed-size output
+    /// holds, see [`SortBy::into_sorted_array`](crate::SortBy::into_sorted_array).
+    WrongItemCount {
+        /// Number of items the output holds.
+        capacity: usize,
+        /// Number of items the iterator yielded. Reading stops after the
+        /// first item past `capacity`, so this is at most `capacity + 1`.
+        len: usize,
+    },
+    /// A merged input is out of order under the comparator of the merge,
+    /// see [`SortedVec::merge`](crate::SortedVec::merge).
+    IncompatibleOrder {
+        /// Position of the offending input among the merged inputs.
+        input: usize,
+        /// Index of the first item that sorts before its predecessor.
+        index: usize,
+    },
+    /// A sort spec names a field that cannot be sorted by.
+    UnknownField(UnknownField),
+    /// The values a strict [`JsonOrder`](crate::json::JsonOrder) selected
+    /// have different types. Requires the `json` feature.
+    #[cfg(feature = "json")]
+    MixedTypes(MixedTypes),
+    /// A pagination cursor was not made for the sort keys. Requires the
+    /// `cursor` feature.
+    #[cfg(feature = "cursor")]
+    InvalidCursor,
+    /// Arrow cannot compare or take the values of a column, e.g. a column of
+    /// an unsupported type. Requires the `arrow` feature.
+    #[cfg(feature = "arrow")]
+    Arrow {
+        /// What Arrow reported.
+        message: String,
+    },
+    /// The sort already yielded items through `next()`, which converting it
+    /// would silently leave out, see
+    /// [`SortBy::has_started`](crate::SortBy::has_started).
+    AlreadyStarted,
+}
+
+impl From<TryReserveError> for SortError {
+    fn from(error: TryReserveError) -> SortError {
+        SortError::AllocationFailed(error)
+    }
+}
+
+impl From<UnknownField> for SortError {
+    fn from(error: UnknownField) -> SortError {
+        SortError::UnknownField(error)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<MixedTypes> for SortError {
+    fn from(error: MixedTypes) -> SortError {
+        SortError::MixedTypes(error)
+    }
+}
+
+#[cfg(feature = "cursor")]
+impl From<InvalidCursor> for SortError {
+    fn from(_: InvalidCursor) -> SortError {
+        SortError::InvalidCursor
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow_schema::ArrowError> for SortError {
+    fn from(error: arrow_schema::ArrowError) -> SortError {
+        use alloc::string::ToString;
+
+        SortError::Arrow {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl core::fmt::Display for SortError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SortError::ComparatorPanicked {
+                message: Some(message),
+            } => write!(f, "comparator panicked: {}", message),
+            SortError::ComparatorPanicked { message: None } => write!(f, "comparator panicked"),
+            SortError::IncomparableKeys { index } => {
+                write!(f, "the key of item {} cannot be compared", index)
+            }
+            SortError::KeyExtractionFailed { index, message } => {
+                write!(f, "cannot extract the key of item {}: {}", index, message)
+            }
+            SortError::AllocationFailed(error) => write!(f, "cannot allocate: {}", error),
+            SortError::LimitExceeded { limit } => write!(f, "more than {} items to sort", limit),
+            SortError::WrongItemCount { capacity, len } if len > capacity => {
+                write!(f, "more than {} items to sort", capacity)
+            }
+            SortError::WrongItemCount { capacity, len } => {
+                write!(f, "expected {} items to sort, got {}", capacity, len)
+            }
+            SortError::IncompatibleOrder { input, index } => write!(
+                f,
+                "input {} is out of order at index {} under the target comparator",
+                input, index
+            ),
+            SortError::UnknownField(error) => error.fmt(f),
+            #[cfg(feature = "json")]
+            SortError::MixedTypes(error) => error.fmt(f),
+            #[cfg(feature = "cursor")]
+            SortError::InvalidCursor => write!(f, "invalid pagination cursor"),
+            #[cfg(feature = "arrow")]
+            SortError::Arrow { message } => write!(f, "cannot sort column: {}", message),
+            SortError::AlreadyStarted => write!(f, "the sort already yielded items"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SortError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SortError::AllocationFailed(error) => Some(error),
+            SortError::UnknownField(error) => Some(error),
+            #[cfg(feature = "json")]
+            SortError::MixedTypes(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use super::SortError;
+    use crate::UnknownField;
+
+    #[test]
+    fn converts_other_errors() {
+        let error = SortError::LimitExceeded { limit: 3 };
+        assert_eq!(error.to_string(), "more than 3 items to sort");
+
+        let reserve = Vec::<u64>::new().try_reserve(usize::MAX).unwrap_err();
+        assert!(matches!(
+            SortError::from(reserve),
+            SortError::AllocationFailed(_)
+        ));
+
+        let error = SortError::from(UnknownField {
+            field: "age".into(),
+        });
+        assert_eq!(error.to_string(), "cannot sort by unknown field `age`");
+    }
+}
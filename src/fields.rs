@@ -0,0 +1,381 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use serde::ser::{self, Serialize};
+use serde_json::{Error, Value};
+
+use crate::json::JsonOrder;
+use crate::{trace, SortError};
+
+/// Sorts `items` by the value of their serialized field `field`, see
+/// [`SortBy::sort_by_serde_field`](crate::SortBy::sort_by_serde_field).
+/// Only that field of each item is serialized, once.
+pub(crate) fn sort_by_serde_field<T: Serialize>(
+    items: Vec<T>,
+    field: &str,
+    descending: bool,
+) -> Result<Vec<T>, SortError> {
+    let order = JsonOrder::new();
+    let mut keyed = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        let value = item.serialize(FieldSerializer { field }).map_err(|e| {
+            SortError::KeyExtractionFailed {
+                index,
+                message: e.to_string(),
+            }
+        })?;
+        keyed.push((value.unwrap_or(Value::Null), item));
+    }
+
+    let _span = trace::sort(&keyed);
+    keyed.sort_by(|a, b| {
+        let ordering = order.compare(&a.0, &b.0);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    Ok(keyed.into_iter().map(|(_, item)| item).collect())
+}
+
+/// Serializes only the field `field` of a struct or map, and nothing of
+/// other values, which have no fields.
+struct FieldSerializer<'f> {
+    field: &'f str,
+}
+
+/// Captures the value of the field while the other fields are skipped
+/// without being serialized.
+struct Capture<'f> {
+    field: &'f str,
+    value: Option<Value>,
+    /// Whether the last map key was the field.
+    matched: bool,
+}
+
+/// Ignores the elements of values without fields, like sequences.
+struct Skip;
+
+macro_rules! no_field {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Option<Value>, Error> {
+                Ok(None)
+            }
+        )*
+    };
+}
+
+impl<'f> ser::Serializer for FieldSerializer<'f> {
+    type Ok = Option<Value>;
+    type Error = Error;
+    type SerializeSeq = Skip;
+    type SerializeTuple = Skip;
+    type SerializeTupleStruct = Skip;
+    type SerializeTupleVariant = Skip;
+    type SerializeMap = Capture<'f>;
+    type SerializeStruct = Capture<'f>;
+    type SerializeStructVariant = Skip;
+
+    no_field! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<V: Serialize + ?Sized>(self, value: &V) -> Result<Option<Value>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<V: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &V,
+    ) -> Result<Option<Value>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<V: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &V,
+    ) -> Result<Option<Value>, Error> {
+        Ok(None)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Skip, Error> {
+        Ok(Skip)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Skip, Error> {
+        Ok(Skip)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Skip, Error> {
+        Ok(Skip)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Skip, Error> {
+        Ok(Skip)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Capture<'f>, Error> {
+        Ok(Capture::new(self.field))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Capture<'f>, Error> {
+        Ok(Capture::new(self.field))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Skip, Error> {
+        Ok(Skip)
+    }
+}
+
+impl<'f> Capture<'f> {
+    fn new(field: &'f str) -> Capture<'f> {
+        Capture {
+            field,
+            value: None,
+            matched: false,
+        }
+    }
+}
+
+impl<'f> ser::SerializeStruct for Capture<'f> {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<V: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &V,
+    ) -> Result<(), Error> {
+        if key == self.field {
+            self.value = Some(serde_json::to_value(value)?);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<Value>, Error> {
+        Ok(self.value)
+    }
+}
+
+impl<'f> ser::SerializeMap for Capture<'f> {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_key<K: Serialize + ?Sized>(&mut self, key: &K) -> Result<(), Error> {
+        // only string keys can name the field, and those serialize cheaply
+        self.matched =
+            matches!(serde_json::to_value(key)?, Value::String(ref key) if key == self.field);
+        Ok(())
+    }
+
+    fn serialize_value<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        if self.matched {
+            self.value = Some(serde_json::to_value(value)?);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<Value>, Error> {
+        Ok(self.value)
+    }
+}
+
+macro_rules! skip {
+    ($($trait:ident::$method:ident;)*) => {
+        $(
+            impl ser::$trait for Skip {
+                type Ok = Option<Value>;
+                type Error = Error;
+
+                fn $method<V: Serialize + ?Sized>(&mut self, _: &V) -> Result<(), Error> {
+                    Ok(())
+                }
+
+                fn end(self) -> Result<Option<Value>, Error> {
+                    Ok(None)
+                }
+            }
+        )*
+    };
+}
+
+skip! {
+    SerializeSeq::serialize_element;
+    SerializeTuple::serialize_element;
+    SerializeTupleStruct::serialize_field;
+    SerializeTupleVariant::serialize_field;
+}
+
+impl ser::SerializeStructVariant for Skip {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<V: Serialize + ?Sized>(
+        &mut self,
+        _: &'static str,
+        _: &V,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<Value>, Error> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+
+    use serde::{Serialize, Serializer};
+
+    use crate::{SortByIteratorExt, SortError};
+
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    struct User {
+        name: &'static str,
+        age: Option<u32>,
+    }
+
+    #[test]
+    fn sorts_by_named_field() {
+        let user = |name, age| User { name, age };
+        let input = vec![user("b", Some(40)), user("a", None), user("c", Some(30))];
+
+        let actual: Vec<_> = input
+            .clone()
+            .into_iter()
+            .sort_by_serde_field("age")
+            .unwrap()
+            .map(|u| u.name)
+            .collect();
+        assert_eq!(actual, vec!["a", "c", "b"]);
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by(|u| u.age.is_some())
+            .sort_by_serde_field_desc("name")
+            .unwrap()
+            .map(|u| u.name)
+            .collect();
+        assert_eq!(actual, vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn treats_missing_fields_as_null() {
+        let input = vec![(2, "x"), (1, "y")];
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by_serde_field("age")
+            .unwrap()
+            .collect();
+
+        assert_eq!(actual, vec![(2, "x"), (1, "y")]);
+    }
+
+    #[test]
+    fn reads_map_entries() {
+        let row = |score: u32| {
+            let mut row = BTreeMap::new();
+            row.insert("score", score);
+            row.insert("rank", 10 - score);
+            row
+        };
+
+        let actual: Vec<_> = vec![row(3), row(1), row(2)]
+            .into_iter()
+            .sort_by_serde_field("score")
+            .unwrap()
+            .map(|row| row["score"])
+            .collect();
+
+        assert_eq!(actual, vec![1, 2, 3]);
+    }
+
+    struct Broken;
+
+    impl Serialize for Broken {
+        fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("broken"))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Row {
+        id: u32,
+        broken: Broken,
+    }
+
+    #[test]
+    fn serializes_only_the_named_field() {
+        let rows = vec![
+            Row {
+                id: 2,
+                broken: Broken,
+            },
+            Row {
+                id: 1,
+                broken: Broken,
+            },
+        ];
+
+        let actual: Vec<_> = rows
+            .into_iter()
+            .sort_by_serde_field("id")
+            .unwrap()
+            .map(|row| row.id)
+            .collect();
+        assert_eq!(actual, vec![1, 2]);
+
+        let rows = vec![Row {
+            id: 2,
+            broken: Broken,
+        }];
+        let actual = rows.into_iter().sort_by_serde_field("broken");
+        assert_eq!(
+            actual.err(),
+            Some(SortError::KeyExtractionFailed {
+                index: 0,
+                message: "broken".into(),
+            })
+        );
+    }
+}
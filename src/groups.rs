@@ -0,0 +1,105 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Groups `items` by `group_key`, keeping their order within each group, and
+/// orders the groups by `aggregate`. Groups with equal aggregates keep the
+/// order in which they first appeared.
+pub(crate) fn sort_groups<T, K, A, G, F>(
+    items: Vec<T>,
+    group_key: G,
+    aggregate: F,
+    descending: bool,
+) -> Vec<T>
+where
+    K: Eq + Hash,
+    A: PartialOrd,
+    G: Fn(&T) -> K,
+    F: Fn(&[T]) -> A,
+{
+    let len = items.len();
+    let mut index = HashMap::new();
+    let mut groups: Vec<Vec<T>> = Vec::new();
+    for item in items {
+        let i = *index.entry(group_key(&item)).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[i].push(item);
+    }
+
+    let mut groups: Vec<_> = groups
+        .into_iter()
+        .map(|group| (aggregate(&group), group))
+        .collect();
+    groups.sort_by(|a, b| {
+        let ordering = if descending {
+            b.0.partial_cmp(&a.0)
+        } else {
+            a.0.partial_cmp(&b.0)
+        };
+        ordering.unwrap_or(Ordering::Equal)
+    });
+
+    let mut vec = Vec::with_capacity(len);
+    for (_, group) in groups {
+        vec.extend(group);
+    }
+    vec
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SortByIteratorExt;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Message {
+        thread: &'static str,
+        time: u32,
+    }
+
+    fn message(thread: &'static str, time: u32) -> Message {
+        Message { thread, time }
+    }
+
+    #[test]
+    fn orders_threads_by_most_recent_message() {
+        let messages = vec![
+            message("a", 3),
+            message("b", 7),
+            message("a", 1),
+            message("c", 5),
+            message("b", 2),
+        ];
+
+        let actual: Vec<_> = messages
+            .into_iter()
+            .sort_by(|m| m.time)
+            .sort_groups_by_aggregate_desc(|m| m.thread, |group| group.iter().map(|m| m.time).max())
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                message("b", 2),
+                message("b", 7),
+                message("c", 5),
+                message("a", 1),
+                message("a", 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn preserves_input_order_within_groups() {
+        let actual: Vec<_> = vec![(1, 'x'), (2, 'y'), (1, 'z'), (2, 'w'), (2, 'v')]
+            .into_iter()
+            .sort_groups_by_aggregate(|v| v.0, |group| group.len())
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec![(1, 'x'), (1, 'z'), (2, 'y'), (2, 'w'), (2, 'v')]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::CompareFn;
+
+/// A priority queue ordered by the comparator of the sort it came from: `pop`
+/// returns the item the sort would yield first. Unlike a `BinaryHeap`, the
+/// items don't need to implement `Ord`.
+///
+/// Created by [`SortBy::into_binary_heap`](crate::SortBy::into_binary_heap).
+/// A sorted buffer already is a valid heap, so the conversion is free. Items
+/// that compare equal are popped in no particular order.
+///
+/// ```
+/// use sortby::*;
+///
+/// let mut queue = vec![(2, "b"), (1, "a"), (3, "c")]
+///     .into_iter()
+///     .sort_by(|v| v.0)
+///     .into_binary_heap();
+/// queue.push((0, "urgent"));
+/// assert_eq!(queue.pop(), Some((0, "urgent")));
+/// assert_eq!(queue.pop(), Some((1, "a")));
+/// assert_eq!(queue.len(), 2);
+/// ```
+pub struct SortedHeap<'a, T> {
+    items: Vec<T>,
+    compare: CompareFn<'a, T>,
+}
+
+impl<'a, T> SortedHeap<'a, T> {
+    /// `items` must already be sorted by `compare`.
+    pub(crate) fn from_sorted(items: Vec<T>, compare: CompareFn<'a, T>) -> SortedHeap<'a, T> {
+        SortedHeap { items, compare }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The item `pop` would return.
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    /// Adds an item in `O(log n)`.
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        let mut child = self.items.len() - 1;
+        while child > 0 {
+            let parent = (child - 1) / 2;
+            if (self.compare)(&self.items[child], &self.items[parent]) != Ordering::Less {
+                break;
+            }
+            self.items.swap(child, parent);
+            child = parent;
+        }
+    }
+
+    /// Removes the first item in the order of the comparator in `O(log n)`.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let item = self.items.swap_remove(0);
+        let len = self.items.len();
+        let mut parent = 0;
+        loop {
+            let mut first = parent;
+            for child in [2 * parent + 1, 2 * parent + 2] {
+                if child < len
+                    && (self.compare)(&self.items[child], &self.items[first]) == Ordering::Less
+                {
+                    first = child;
+                }
+            }
+            if first == parent {
+                return Some(item);
+            }
+            self.items.swap(parent, first);
+            parent = first;
+        }
+    }
+
+    /// Sorts the remaining items into a `Vec`.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let compare = &self.compare;
+        self.items.sort_by(|a, b| compare(a, b));
+        self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn pops_in_sort_order_after_pushes() {
+        let mut heap = (0..50u32)
+            .map(|i| (i * 7) % 50)
+            .sort_by_desc(|v| *v)
+            .into_binary_heap();
+        for v in [100, 25, 0, 75] {
+            heap.push(v);
+        }
+        assert_eq!(heap.peek(), Some(&100));
+
+        let mut popped = Vec::new();
+        while let Some(v) = heap.pop() {
+            popped.push(v);
+        }
+        let mut expected: Vec<u32> = (0..50).chain([100, 25, 0, 75]).collect();
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(popped, expected);
+        assert!(heap.is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::vec::Vec;
+
+/// How [`SortByIteratorExt::histogram_by`](crate::SortByIteratorExt::histogram_by)
+/// places the bin boundaries.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Binning {
+    /// Bins of the same width between the smallest and largest key.
+    #[default]
+    EqualWidth,
+    /// Bins with about the same number of keys. Equal keys always share a
+    /// bin, so with many ties bins are uneven and fewer than requested.
+    EqualFrequency,
+}
+
+/// One bin of a histogram: the keys in `start..end`, or `start..=end` for the
+/// last bin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+/// Sorts `keys` and counts them into `k` bins, skipping NaN.
+pub(crate) fn histogram(mut keys: Vec<f64>, k: usize, binning: Binning) -> Vec<Bin> {
+    assert!(k > 0, "cannot split into zero bins");
+    keys.retain(|key| !key.is_nan());
+    keys.sort_by(f64::total_cmp);
+    let (Some(&min), Some(&max)) = (keys.first(), keys.last()) else {
+        return Vec::new();
+    };
+
+    match binning {
+        Binning::EqualWidth => {
+            let width = (max - min) / k as f64;
+            let mut bins: Vec<Bin> = (0..k)
+                .map(|i| Bin {
+                    start: min + width * i as f64,
+                    end: if i + 1 == k {
+                        max
+                    } else {
+                        min + width * (i + 1) as f64
+                    },
+                    count: 0,
+                })
+                .collect();
+            for key in keys {
+                let bin = if width > 0.0 {
+                    (((key - min) / width) as usize).min(k - 1)
+                } else {
+                    0
+                };
+                bins[bin].count += 1;
+            }
+            bins
+        }
+        Binning::EqualFrequency => {
+            // like `SortBy::with_quantile_bucket`, a new bin starts where the
+            // bucket of a position changes, but never within a run of ties
+            let len = keys.len() as u128;
+            let mut bins: Vec<Bin> = Vec::with_capacity(k);
+            let mut current = None;
+            for (i, &key) in keys.iter().enumerate() {
+                if i == 0 || key != keys[i - 1] {
+                    let bucket = i as u128 * k as u128 / len;
+                    if current != Some(bucket) {
+                        if let Some(bin) = bins.last_mut() {
+                            bin.end = key;
+                        }
+                        bins.push(Bin {
+                            start: key,
+                            end: key,
+                            count: 0,
+                        });
+                        current = Some(bucket);
+                    }
+                }
+                if let Some(bin) = bins.last_mut() {
+                    bin.count += 1;
+                }
+            }
+            if let Some(bin) = bins.last_mut() {
+                bin.end = max;
+            }
+            bins
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bin, Binning};
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn counts_equal_width_bins() {
+        let bins = [0.0, 1.0, 2.5, 9.0, 10.0, f64::NAN].iter().histogram_by(
+            |v| **v,
+            4,
+            Binning::EqualWidth,
+        );
+
+        let counts: Vec<_> = bins.iter().map(|bin| bin.count).collect();
+        assert_eq!(counts, vec![2, 1, 0, 2]);
+        assert_eq!(bins[1].start, 2.5);
+        assert_eq!(bins[3].end, 10.0);
+    }
+
+    #[test]
+    fn keeps_ties_in_one_equal_frequency_bin() {
+        let bins = [1, 2, 2, 2, 2, 3, 4, 5].iter().histogram_by(
+            |v| **v as f64,
+            4,
+            Binning::EqualFrequency,
+        );
+
+        assert_eq!(
+            bins,
+            vec![
+                Bin {
+                    start: 1.0,
+                    end: 3.0,
+                    count: 5
+                },
+                Bin {
+                    start: 3.0,
+                    end: 4.0,
+                    count: 1
+                },
+                Bin {
+                    start: 4.0,
+                    end: 5.0,
+                    count: 2
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// Stably orders `items` by the key `f` returns. Each distinct key is mapped
+/// to its rank once, so the sort itself only compares integers, which pays
+/// off when few distinct keys are expensive to compare, e.g. strings.
+pub(crate) fn sort_interned<T, K, F>(items: Vec<T>, f: F, descending: bool) -> Vec<T>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    let mut ranks = BTreeMap::new();
+    for item in &items {
+        ranks.entry(f(item)).or_insert(0);
+    }
+    let distinct = ranks.len();
+    for (rank, value) in ranks.values_mut().enumerate() {
+        *value = if descending { distinct - rank } else { rank };
+    }
+
+    let mut ranked: Vec<_> = items
+        .into_iter()
+        .map(|item| (ranks[&f(&item)], item))
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn orders_by_interned_key() {
+        let input = vec![("US", 3), ("DE", 1), ("US", 1), ("FR", 2), ("DE", 2)];
+
+        let actual: Vec<_> = input
+            .clone()
+            .into_iter()
+            .sort_by(|v| v.1)
+            .sort_by_interned(|v| v.0)
+            .collect();
+        assert_eq!(
+            actual,
+            vec![("DE", 1), ("DE", 2), ("FR", 2), ("US", 1), ("US", 3)]
+        );
+
+        let actual: Vec<_> = input.into_iter().sort_by_interned_desc(|v| v.0).collect();
+        assert_eq!(
+            actual,
+            vec![("US", 3), ("US", 1), ("FR", 2), ("DE", 1), ("DE", 2)]
+        );
+    }
+}
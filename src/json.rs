@@ -0,0 +1,390 @@
+//! Sorting of JSON documents by the values [JSON pointers] select in them.
+//! Requires the `json` feature.
+//!
+//! Values of different types are ordered by type first, see
+//! [`JsonOrder`]. A pointer that selects nothing is treated like `null`.
+//!
+//! ```
+//! use serde_json::json;
+//! use sortby::*;
+//! use sortby::json::JsonOrder;
+//!
+//! let docs = vec![json!({"id": 1, "rank": "b"}), json!({"id": 2, "rank": 3}), json!({"id": 3})];
+//! let order = JsonOrder::new();
+//! let ids: Vec<_> = docs
+//!     .into_iter()
+//!     .sort_by_json_pointer("/rank", &order)
+//!     .unwrap()
+//!     .map(|doc| doc["id"].clone())
+//!     .collect();
+//! assert_eq!(ids, vec![json!(3), json!(2), json!(1)]);
+//! ```
+//!
+//! [JSON pointers]: https://www.rfc-editor.org/rfc/rfc6901
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+
+use serde_json::{Number, Value};
+
+use crate::{Compare, Direction};
+
+/// The type of a JSON value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JsonType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    pub fn of(value: &Value) -> JsonType {
+        match value {
+            Value::Null => JsonType::Null,
+            Value::Bool(_) => JsonType::Bool,
+            Value::Number(_) => JsonType::Number,
+            Value::String(_) => JsonType::String,
+            Value::Array(_) => JsonType::Array,
+            Value::Object(_) => JsonType::Object,
+        }
+    }
+}
+
+const DEFAULT_TYPE_ORDER: [JsonType; 6] = [
+    JsonType::Null,
+    JsonType::Bool,
+    JsonType::Number,
+    JsonType::String,
+    JsonType::Array,
+    JsonType::Object,
+];
+
+/// How numbers are compared.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NumberOrder {
+    /// Integers are compared exactly, even beyond the 53 bits an `f64` can
+    /// hold. Comparisons involving a fraction go through `f64`.
+    #[default]
+    Exact,
+    /// All numbers are converted to `f64` first, so large integers that
+    /// round to the same `f64` compare equal.
+    Float,
+}
+
+/// How JSON values are ordered.
+///
+/// By default, values of different types are ordered
+/// `null < bool < number < string < array < object`, numbers are compared
+/// exactly, strings by their bytes, and arrays and objects element by
+/// element.
+#[derive(Clone, Debug)]
+pub struct JsonOrder {
+    types: Vec<JsonType>,
+    strict: bool,
+    numbers: NumberOrder,
+}
+
+impl JsonOrder {
+    pub fn new() -> JsonOrder {
+        JsonOrder {
+            types: DEFAULT_TYPE_ORDER.to_vec(),
+            strict: false,
+            numbers: NumberOrder::default(),
+        }
+    }
+
+    /// Orders values of different types by their position in `types`. Types
+    /// not listed come after the listed ones, in the default order.
+    pub fn type_order(mut self, types: &[JsonType]) -> JsonOrder {
+        self.types = types.to_vec();
+        for t in DEFAULT_TYPE_ORDER {
+            if !self.types.contains(&t) {
+                self.types.push(t);
+            }
+        }
+        self
+    }
+
+    /// Fails sorts with [`SortError::MixedTypes`](crate::SortError::MixedTypes)
+    /// if the selected values have more than one type besides `null`.
+    pub fn strict(mut self) -> JsonOrder {
+        self.strict = true;
+        self
+    }
+
+    pub fn numbers(mut self, numbers: NumberOrder) -> JsonOrder {
+        self.numbers = numbers;
+        self
+    }
+
+    pub fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        match (a, b) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => self.compare_numbers(a, b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| self.compare(a, b))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Value::Object(a), Value::Object(b)) => a
+                .iter()
+                .zip(b)
+                .map(|((ka, va), (kb, vb))| ka.cmp(kb).then_with(|| self.compare(va, vb)))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            _ => self.rank(a).cmp(&self.rank(b)),
+        }
+    }
+
+    /// Checks `values` against [`strict`](JsonOrder::strict) mode.
+    pub fn check<'v>(&self, values: impl IntoIterator<Item = &'v Value>) -> Result<(), MixedTypes> {
+        if !self.strict {
+            return Ok(());
+        }
+        let mut first = None;
+        for (index, value) in values.into_iter().enumerate() {
+            let t = JsonType::of(value);
+            match first {
+                _ if t == JsonType::Null => {}
+                None => first = Some(t),
+                Some(first) if first != t => {
+                    return Err(MixedTypes {
+                        index,
+                        expected: first,
+                        found: t,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn rank(&self, value: &Value) -> usize {
+        let t = JsonType::of(value);
+        self.types.iter().position(|other| *other == t).unwrap()
+    }
+
+    fn compare_numbers(&self, a: &Number, b: &Number) -> Ordering {
+        let exact = |n: &Number| {
+            n.as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from))
+        };
+        if self.numbers == NumberOrder::Exact {
+            if let (Some(a), Some(b)) = (exact(a), exact(b)) {
+                return a.cmp(&b);
+            }
+        }
+        let float = |n: &Number| n.as_f64().unwrap_or(0.0);
+        float(a).partial_cmp(&float(b)).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Default for JsonOrder {
+    fn default() -> Self {
+        JsonOrder::new()
+    }
+}
+
+/// Orders JSON documents by the value a pointer selects, see
+/// [`SortByIteratorExt::sort_by_json_pointer`](crate::SortByIteratorExt::sort_by_json_pointer).
+#[derive(Clone)]
+pub struct ByJsonPointer<'a> {
+    pointer: &'a str,
+    order: &'a JsonOrder,
+}
+
+impl<'a> ByJsonPointer<'a> {
+    pub(crate) fn new(pointer: &'a str, order: &'a JsonOrder) -> ByJsonPointer<'a> {
+        ByJsonPointer { pointer, order }
+    }
+}
+
+pub(crate) fn select<'v>(doc: &'v Value, pointer: &str) -> &'v Value {
+    doc.pointer(pointer).unwrap_or(&Value::Null)
+}
+
+impl<'a, T: Borrow<Value>> Compare<T> for ByJsonPointer<'a> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.order.compare(
+            select(a.borrow(), self.pointer),
+            select(b.borrow(), self.pointer),
+        )
+    }
+}
+
+/// Orders JSON documents by several pointers, most significant first, see
+/// [`SortByIteratorExt::sort_by_json_pointers`](crate::SortByIteratorExt::sort_by_json_pointers).
+#[derive(Clone)]
+pub struct ByJsonPointers<'a> {
+    levels: &'a [(&'a str, Direction)],
+    order: &'a JsonOrder,
+}
+
+impl<'a> ByJsonPointers<'a> {
+    pub(crate) fn new(
+        levels: &'a [(&'a str, Direction)],
+        order: &'a JsonOrder,
+    ) -> ByJsonPointers<'a> {
+        ByJsonPointers { levels, order }
+    }
+}
+
+impl<'a, T: Borrow<Value>> Compare<T> for ByJsonPointers<'a> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        let (a, b) = (a.borrow(), b.borrow());
+        for (pointer, direction) in self.levels {
+            let ordering = self.order.compare(select(a, pointer), select(b, pointer));
+            let ordering = match direction {
+                Direction::Ascending => ordering,
+                Direction::Descending => ordering.reverse(),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+/// Returned by sorts with a [`strict`](JsonOrder::strict) [`JsonOrder`] for
+/// values of different types.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MixedTypes {
+    /// Zero-based position of the first document whose value has another
+    /// type than the ones before it.
+    pub index: usize,
+    pub expected: JsonType,
+    pub found: JsonType,
+}
+
+impl core::fmt::Display for MixedTypes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "cannot sort {:?} values among {:?} values (document #{})",
+            self.found,
+            self.expected,
+            self.index + 1
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MixedTypes {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{SortByIteratorExt, SortError};
+
+    fn sort(docs: &[Value], order: &JsonOrder) -> Result<Vec<Value>, SortError> {
+        Ok(docs
+            .iter()
+            .sort_by_json_pointer("/v", order)?
+            .map(|doc| doc["v"].clone())
+            .collect())
+    }
+
+    #[test]
+    fn orders_mixed_types() {
+        let docs = [
+            json!({"v": "a"}),
+            json!({"v": [1]}),
+            json!({"v": 2}),
+            json!({}),
+            json!({"v": true}),
+            json!({"v": {"k": 1}}),
+        ];
+
+        assert_eq!(
+            sort(&docs, &JsonOrder::new()),
+            Ok(vec![
+                json!(null),
+                json!(true),
+                json!(2),
+                json!("a"),
+                json!([1]),
+                json!({"k": 1})
+            ])
+        );
+
+        let order = JsonOrder::new().type_order(&[JsonType::String, JsonType::Number]);
+        let actual = sort(&docs, &order).unwrap();
+        assert_eq!(&actual[..3], &[json!("a"), json!(2), json!(null)]);
+
+        assert_eq!(
+            sort(&docs, &JsonOrder::new().strict()),
+            Err(SortError::MixedTypes(MixedTypes {
+                index: 1,
+                expected: JsonType::String,
+                found: JsonType::Array
+            }))
+        );
+    }
+
+    #[test]
+    fn compares_large_integers_exactly() {
+        let docs = [
+            json!({"v": 9007199254740993u64}),
+            json!({"v": 9007199254740992u64}),
+            json!({"v": -1.5}),
+            json!({"v": null}),
+        ];
+
+        assert_eq!(
+            sort(&docs, &JsonOrder::new().strict()),
+            Ok(vec![
+                json!(null),
+                json!(-1.5),
+                json!(9007199254740992u64),
+                json!(9007199254740993u64)
+            ])
+        );
+
+        let float = sort(&docs, &JsonOrder::new().numbers(NumberOrder::Float)).unwrap();
+        assert_eq!(float[2], json!(9007199254740993u64));
+    }
+
+    #[test]
+    fn sorts_by_several_pointers() {
+        let docs = [
+            json!({"id": 1, "priority": 1, "created_at": "2024-03-01"}),
+            json!({"id": 2, "priority": 3, "created_at": "2024-02-01"}),
+            json!({"id": 3, "priority": 1, "created_at": "2024-01-01"}),
+            json!({"id": 4, "priority": "high", "created_at": "2024-01-01"}),
+        ];
+        let levels = [
+            ("/priority", Direction::Descending),
+            ("/created_at", Direction::Ascending),
+        ];
+
+        let ids: Vec<_> = docs
+            .iter()
+            .sort_by_json_pointers(&levels, &JsonOrder::new())
+            .unwrap()
+            .map(|doc| doc["id"].clone())
+            .collect();
+        assert_eq!(ids, vec![json!(4), json!(2), json!(3), json!(1)]);
+
+        let strict = JsonOrder::new().strict();
+        assert_eq!(
+            docs.iter().sort_by_json_pointers(&levels, &strict).err(),
+            Some(SortError::MixedTypes(MixedTypes {
+                index: 3,
+                expected: JsonType::Number,
+                found: JsonType::String
+            }))
+        );
+    }
+}
@@ -0,0 +1,501 @@
+//! Key extractors for common string formats, to pass to
+//! [`sort_by`](crate::SortByIteratorExt::sort_by) and friends.
+//!
+//! ```
+//! use sortby::*;
+//!
+//! let chapters = vec!["Chapter 12", "Preface", "Chapter 2", "Chapter 1: Intro"];
+//! let sorted: Vec<_> = chapters.into_iter().sort_by(key::first_uint(|v: &&str| *v)).collect();
+//! assert_eq!(sorted, vec!["Chapter 1: Intro", "Chapter 2", "Chapter 12", "Preface"]);
+//! ```
+
+use core::convert::TryFrom;
+use core::net::IpAddr;
+use core::time::Duration;
+
+#[cfg(feature = "rust_decimal")]
+use crate::NumberFormat;
+
+/// Where items go whose string a key extractor cannot read.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Fallback {
+    First,
+    #[default]
+    Last,
+}
+
+impl Fallback {
+    fn rank(self) -> u8 {
+        match self {
+            Fallback::First => 0,
+            Fallback::Last => 2,
+        }
+    }
+}
+
+/// The key of [`first_uint`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FirstUint {
+    rank: u8,
+    value: u128,
+}
+
+impl FirstUint {
+    /// The number found, if any.
+    pub fn value(&self) -> Option<u128> {
+        if self.rank == 1 {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+/// Orders by the first run of ASCII digits in the string `f` returns, read
+/// as an unsigned integer, e.g. the `12` in `Chapter 12`. Strings without
+/// digits come last, and numbers too large for a `u128` saturate.
+pub fn first_uint<T, F>(f: F) -> impl Fn(&T) -> FirstUint
+where
+    F: Fn(&T) -> &str,
+{
+    first_uint_or(f, Fallback::Last)
+}
+
+/// Like [`first_uint`], with `fallback` deciding where strings without
+/// digits go.
+pub fn first_uint_or<T, F>(f: F, fallback: Fallback) -> impl Fn(&T) -> FirstUint
+where
+    F: Fn(&T) -> &str,
+{
+    move |item| {
+        let s = f(item);
+        let digits = s.find(|c: char| c.is_ascii_digit()).map(|start| {
+            let rest = &s[start..];
+            &rest[..rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len())]
+        });
+        match digits {
+            Some(digits) => FirstUint {
+                rank: 1,
+                value: digits.bytes().fold(0u128, |value, digit| {
+                    value
+                        .saturating_mul(10)
+                        .saturating_add(u128::from(digit - b'0'))
+                }),
+            },
+            None => FirstUint {
+                rank: fallback.rank(),
+                value: 0,
+            },
+        }
+    }
+}
+
+/// How [`ip_with`] orders IPv4 and IPv6 addresses relative to each other.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum IpFamilies {
+    #[default]
+    V4First,
+    V6First,
+    /// Orders IPv4 addresses as their IPv4-mapped IPv6 form `::ffff:a.b.c.d`.
+    Mixed,
+}
+
+/// The key of [`ip`] and [`ip_with`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct IpKey {
+    rank: u8,
+    family: u8,
+    bits: u128,
+    prefix: u8,
+    addr: Option<IpAddr>,
+}
+
+impl IpKey {
+    /// The address and prefix length parsed, if any. Plain addresses have
+    /// the full length of 32 or 128 bits.
+    pub fn value(&self) -> Option<(IpAddr, u8)> {
+        self.addr.map(|addr| (addr, self.prefix))
+    }
+}
+
+/// Orders IP addresses like `10.0.0.2` or CIDR blocks like `10.0.0.0/8` in
+/// the string `f` returns numerically, IPv4 before IPv6. A block comes
+/// before the narrower blocks and addresses it starts with. Strings that are
+/// neither come last.
+pub fn ip<T, F>(f: F) -> impl Fn(&T) -> IpKey
+where
+    F: Fn(&T) -> &str,
+{
+    ip_with(f, IpFamilies::default(), Fallback::Last)
+}
+
+/// Like [`ip`], with `families` deciding how IPv4 and IPv6 are ordered and
+/// `fallback` where unparsable strings go.
+pub fn ip_with<T, F>(f: F, families: IpFamilies, fallback: Fallback) -> impl Fn(&T) -> IpKey
+where
+    F: Fn(&T) -> &str,
+{
+    move |item| match parse_cidr(f(item).trim()) {
+        Some((addr, prefix)) => {
+            let (family, bits) = match (addr, families) {
+                (IpAddr::V4(v4), IpFamilies::Mixed) => (0, u128::from(v4.to_ipv6_mapped())),
+                (IpAddr::V4(v4), IpFamilies::V4First) => (0, u128::from(u32::from(v4))),
+                (IpAddr::V4(v4), IpFamilies::V6First) => (1, u128::from(u32::from(v4))),
+                (IpAddr::V6(v6), IpFamilies::V6First | IpFamilies::Mixed) => (0, u128::from(v6)),
+                (IpAddr::V6(v6), IpFamilies::V4First) => (1, u128::from(v6)),
+            };
+            IpKey {
+                rank: 1,
+                family,
+                bits,
+                prefix,
+                addr: Some(addr),
+            }
+        }
+        None => IpKey {
+            rank: fallback.rank(),
+            family: 0,
+            bits: 0,
+            prefix: 0,
+            addr: None,
+        },
+    }
+}
+
+fn parse_cidr(s: &str) -> Option<(IpAddr, u8)> {
+    let (addr, prefix) = match s.split_once('/') {
+        Some((addr, prefix)) => (addr.parse().ok()?, Some(prefix.parse().ok()?)),
+        None => (s.parse().ok()?, None),
+    };
+    let len = match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    match prefix {
+        Some(prefix) if prefix > len => None,
+        prefix => Some((addr, prefix.unwrap_or(len))),
+    }
+}
+
+/// The key of [`duration`] and [`duration_or`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DurationKey {
+    rank: u8,
+    value: Duration,
+}
+
+impl DurationKey {
+    /// The duration parsed, if any.
+    pub fn value(&self) -> Option<Duration> {
+        if self.rank == 1 {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+/// Orders human duration strings like `90s`, `1h 5m` or `2d` in the string
+/// `f` returns by length, see [`parse_duration`] for the format. Strings
+/// that are not durations come last.
+pub fn duration<T, F>(f: F) -> impl Fn(&T) -> DurationKey
+where
+    F: Fn(&T) -> &str,
+{
+    duration_or(f, Fallback::Last)
+}
+
+/// Like [`duration`], with `fallback` deciding where unparsable strings go.
+pub fn duration_or<T, F>(f: F, fallback: Fallback) -> impl Fn(&T) -> DurationKey
+where
+    F: Fn(&T) -> &str,
+{
+    move |item| match parse_duration(f(item)) {
+        Some(value) => DurationKey { rank: 1, value },
+        None => DurationKey {
+            rank: fallback.rank(),
+            value: Duration::ZERO,
+        },
+    }
+}
+
+/// Parses a human duration: a sequence of numbers, each followed by a unit
+/// of `ns`, `us`, `µs`, `ms`, `s`, `sec`, `m`, `min`, `h`, `hr`, `d` or `w`,
+/// optionally separated by whitespace, like `1h 5m` or `1.5s`. Returns
+/// `None` for anything else, including an empty string.
+///
+/// ```
+/// use std::time::Duration;
+/// use sortby::key::parse_duration;
+///
+/// assert_eq!(parse_duration("1h 5m"), Some(Duration::from_secs(3900)));
+/// assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+/// assert_eq!(parse_duration("soon"), None);
+/// ```
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut nanos: u128 = 0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_len);
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+
+        let unit_nanos = match unit {
+            "ns" => 1,
+            "us" | "µs" => 1_000,
+            "ms" => 1_000_000,
+            "s" | "sec" => NANOS_PER_SEC,
+            "m" | "min" => 60 * NANOS_PER_SEC,
+            "h" | "hr" => 3_600 * NANOS_PER_SEC,
+            "d" => 86_400 * NANOS_PER_SEC,
+            "w" => 604_800 * NANOS_PER_SEC,
+            _ => return None,
+        };
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return None;
+        }
+        let whole: u128 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().ok()?
+        };
+        let mut value = whole.checked_mul(unit_nanos)?;
+        let mut scale = unit_nanos;
+        for digit in fraction.bytes().take(18) {
+            scale /= 10;
+            value += u128::from(digit - b'0') * scale;
+        }
+        nanos = nanos.checked_add(value)?;
+        rest = tail.trim_start();
+    }
+
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).ok()?;
+    Some(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
+
+/// The key of [`decimal`] and [`decimal_with`].
+#[cfg(feature = "rust_decimal")]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DecimalKey {
+    rank: u8,
+    value: rust_decimal::Decimal,
+}
+
+#[cfg(feature = "rust_decimal")]
+impl DecimalKey {
+    /// The amount parsed, if any.
+    pub fn value(&self) -> Option<rust_decimal::Decimal> {
+        if self.rank == 1 {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+/// Orders monetary amounts like `$1,234.50`, `-0.10 EUR` or `(12.00)` in
+/// the string `f` returns exactly, without rounding through `f64`. Strings
+/// that are not amounts come last. Requires the `rust_decimal` feature.
+///
+/// Currency symbols and codes around the number are ignored, and an amount
+/// in parentheses is negative, as in accounting exports.
+///
+/// ```
+/// use sortby::*;
+///
+/// let amounts = vec!["$0.30", "($1,000.00)", "$0.1", "n/a", "0.29999999999999999 USD"];
+/// let sorted: Vec<_> = amounts.into_iter().sort_by(key::decimal(|v: &&str| *v)).collect();
+/// assert_eq!(sorted, vec!["($1,000.00)", "$0.1", "0.29999999999999999 USD", "$0.30", "n/a"]);
+/// ```
+#[cfg(feature = "rust_decimal")]
+pub fn decimal<T, F>(f: F) -> impl Fn(&T) -> DecimalKey
+where
+    F: Fn(&T) -> &str,
+{
+    decimal_with(f, NumberFormat::EN, Fallback::Last)
+}
+
+/// Like [`decimal`], with `format` giving the separators, e.g.
+/// [`NumberFormat::DE`] for `1.234,50 €`, and `fallback` deciding where
+/// unparsable strings go.
+#[cfg(feature = "rust_decimal")]
+pub fn decimal_with<T, F>(
+    f: F,
+    format: NumberFormat,
+    fallback: Fallback,
+) -> impl Fn(&T) -> DecimalKey
+where
+    F: Fn(&T) -> &str,
+{
+    move |item| match parse_amount(f(item), format) {
+        Some(value) => DecimalKey { rank: 1, value },
+        None => DecimalKey {
+            rank: fallback.rank(),
+            value: rust_decimal::Decimal::ZERO,
+        },
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+fn parse_amount(s: &str, format: NumberFormat) -> Option<rust_decimal::Decimal> {
+    let is_currency = |c: char| c.is_alphabetic() || c.is_whitespace() || "$€£¥₹¤".contains(c);
+    let mut s = s.trim_matches(is_currency);
+    let parenthesized = s.starts_with('(') && s.ends_with(')');
+    if parenthesized {
+        s = s[1..s.len() - 1].trim_matches(is_currency);
+    }
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s.trim_start_matches(is_currency)),
+        None => (false, s),
+    };
+
+    let mut normalized = alloc::string::String::with_capacity(s.len() + 1);
+    if negative || parenthesized {
+        normalized.push('-');
+    }
+    let mut previous_is_digit = false;
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            normalized.push(c);
+            previous_is_digit = true;
+        } else if c == format.group && previous_is_digit && !normalized.contains('.') {
+            previous_is_digit = false;
+        } else if c == format.decimal && !normalized.contains('.') {
+            normalized.push('.');
+            previous_is_digit = false;
+        } else {
+            return None;
+        }
+    }
+    if !previous_is_digit || (negative && parenthesized) {
+        return None;
+    }
+    rust_decimal::Decimal::from_str_exact(&normalized).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn extracts_first_number() {
+        let key = first_uint(|v: &&str| *v);
+        assert_eq!(key(&"Chapter 12, part 3").value(), Some(12));
+        assert_eq!(key(&"v007").value(), Some(7));
+        assert_eq!(key(&"none").value(), None);
+        assert_eq!(
+            key(&"999999999999999999999999999999999999999999").value(),
+            Some(u128::MAX)
+        );
+
+        let input = vec!["b", "x10", "a", "x9"];
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by(first_uint_or(|v: &&str| *v, Fallback::First))
+            .collect();
+        assert_eq!(actual, vec!["b", "a", "x9", "x10"]);
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration(" 1h5m 3s "), Some(Duration::from_secs(3903)));
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(172_800)));
+        assert_eq!(parse_duration("1.5m"), Some(Duration::from_secs(90)));
+        assert_eq!(
+            parse_duration(".5s 20µs"),
+            Some(Duration::from_micros(500_020))
+        );
+        for invalid in ["", "5", "h", "1.2.3s", "3 parsecs", "-1s", "1h-"] {
+            assert_eq!(parse_duration(invalid), None, "{}", invalid);
+        }
+
+        let input = vec!["1h 5m", "n/a", "90s", "2d", "59m"];
+        let actual: Vec<_> = input.into_iter().sort_by(duration(|v: &&str| *v)).collect();
+        assert_eq!(actual, vec!["90s", "59m", "1h 5m", "2d", "n/a"]);
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn parses_amounts_exactly() {
+        use rust_decimal::Decimal;
+
+        let amount = |s: &str| decimal(|v: &&str| *v)(&s).value();
+        assert_eq!(amount("$1,234.50"), Some(Decimal::new(123450, 2)));
+        assert_eq!(amount("-€ 0.10"), Some(Decimal::new(-10, 2)));
+        assert_eq!(amount("(12.00) USD"), Some(Decimal::new(-1200, 2)));
+        for invalid in ["", "USD", "1,,000", "1.2.3", "-(1)", "12.", "1e3"] {
+            assert_eq!(amount(invalid), None, "{}", invalid);
+        }
+        assert!(amount("0.30000000000000001") > amount("0.3"));
+
+        let de = decimal_with(|v: &&str| *v, NumberFormat::DE, Fallback::First);
+        assert_eq!(de(&"1.234,50 €").value(), Some(Decimal::new(123450, 2)));
+        assert!(de(&"kostenlos") < de(&"-1.000,00 €"));
+    }
+
+    #[test]
+    fn orders_addresses_numerically() {
+        let input = vec![
+            "10.0.0.19",
+            "::1",
+            "localhost",
+            "10.0.0.2",
+            "10.0.0.0/8",
+            "10.0.0.0/33",
+            "10.0.0.0/24",
+            "::ffff:10.0.0.1",
+        ];
+
+        let actual: Vec<_> = input
+            .clone()
+            .into_iter()
+            .sort_by(ip(|v: &&str| *v))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                "10.0.0.0/8",
+                "10.0.0.0/24",
+                "10.0.0.2",
+                "10.0.0.19",
+                "::1",
+                "::ffff:10.0.0.1",
+                "localhost",
+                "10.0.0.0/33"
+            ]
+        );
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by(ip_with(|v: &&str| *v, IpFamilies::Mixed, Fallback::First))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                "localhost",
+                "10.0.0.0/33",
+                "::1",
+                "10.0.0.0/8",
+                "10.0.0.0/24",
+                "::ffff:10.0.0.1",
+                "10.0.0.2",
+                "10.0.0.19"
+            ]
+        );
+    }
+}
@@ -35,17 +35,222 @@
 //!   .collect();
 //!
 //! println!("{:#?}", sorted);
+//! ```
+//!
+//! # Stability
+//!
+//! All sorts are stable unless stated otherwise: items that compare equal
+//! under every chained level keep their input order, so sorting by the least
+//! significant key first and cascading works as expected. Call `unstable()` on
+//! a sort to trade that guarantee for speed, and `is_stable()` to check it.
 #![warn(rust_2018_idioms)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
+extern crate alloc;
+
+#[cfg(feature = "allocator_api")]
+use alloc::alloc::Allocator;
+use alloc::collections::TryReserveError;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::ops::Bound;
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
+
+mod accents;
+mod array;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod assert;
+mod buckets;
+mod cache;
+#[cfg(feature = "regex")]
+mod capture;
+mod check;
+mod chunks;
+pub mod cmp;
+#[cfg(feature = "icu")]
+pub mod collate;
+mod columns;
+mod compare;
+mod config;
+mod consistency;
+pub mod const_sort;
+#[cfg(feature = "cursor")]
+mod cursor;
+mod default_sort;
+mod deque;
+mod distance;
+mod enum_order;
+mod error;
+#[cfg(feature = "serde")]
+mod fields;
+#[cfg(feature = "std")]
+mod groups;
+mod heap;
+mod histogram;
+mod intern;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod key;
+mod limit;
+mod map;
+mod network;
+pub mod normalize;
+mod numbers;
+mod observer;
+mod online;
+#[cfg(feature = "std")]
+mod panic;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pins;
+mod plan;
+mod quantiles;
+#[cfg(any(feature = "axum", feature = "actix-web"))]
+mod query;
+mod reorder;
+#[cfg(feature = "ndarray")]
+mod rows;
+#[cfg(feature = "rand")]
+mod sample;
+mod saved;
+mod shards;
+#[cfg(feature = "rand")]
+mod shuffle;
+mod sink;
+#[cfg(feature = "sketch")]
+mod sketch;
+mod slice;
+mod sorted_vec;
+mod sorter;
+mod spec;
+#[cfg(feature = "stats")]
+mod stats;
+mod trace;
+mod vec;
+mod watermark;
+
+pub use accents::fold_accents;
+pub use cache::SortedCache;
+#[cfg(feature = "regex")]
+pub use capture::ByRegexCapture;
+pub use check::{AssertSorted, CheckSorted, OutOfOrder, Run};
+pub use chunks::{SortedChunks, SortedPages};
+pub use columns::ColumnSortState;
+pub use compare::{ByKey, ByKeyDesc, Compare, FnCompare, Then};
+pub use config::{Algorithm, Incomparable, SortConfig, UnsupportedGlobalOption};
+#[cfg(feature = "cursor")]
+pub use cursor::{decode_cursor, encode_cursor, InvalidCursor, UnencodableKey};
+pub use default_sort::DefaultSort;
+pub use deque::VecDequeSortByExt;
+pub use distance::{ranking_distance, RankingDistance};
+pub use enum_order::{ByEnumOrder, EnumOrder, VariantOrder};
+pub use error::SortError;
+pub use heap::SortedHeap;
+pub use histogram::{Bin, Binning};
+pub use limit::Overflow;
+pub use map::BTreeMapSortExt;
+#[cfg(feature = "std")]
+pub use map::HashMapSortExt;
+pub use network::{sort_small_by, sort_small_by_desc};
+pub use numbers::{NumberFormat, NumericKey};
+pub use observer::SortObserver;
+#[cfg(feature = "std")]
+pub use observer::{install_global_observer, remove_global_observer};
+pub use online::OnlineSorter;
+#[cfg(feature = "rayon")]
+pub use parallel::Parallelism;
+pub use pins::PinMode;
+pub use plan::{Direction, SortLevel, SortPlan};
+#[cfg(any(feature = "axum", feature = "actix-web"))]
+pub use query::{SortFields, SortQuery, SortQueryRejection};
+pub use reorder::ReorderWithin;
+#[cfg(feature = "ndarray")]
+pub use rows::{ArraySortByExt, RowSortBy};
+pub use saved::{InvalidSavedSpec, Migrations, SAVED_SPEC_VERSION};
+pub use shards::merge_shards;
+#[cfg(feature = "rand")]
+pub use shuffle::Shuffle;
+pub use sink::SortedSink;
+#[cfg(feature = "sketch")]
+pub use sketch::{QuantileSketch, DEFAULT_EPSILON};
+pub use slice::{SliceSortBy, SliceSortByExt};
+pub use sorted_vec::SortedVec;
+pub use sorter::{Sorter, Strategy};
+pub use spec::{AllowedFields, ForbiddenSortKey, InvalidSortSpec, SortKey, SortSpec, UnknownField};
+#[cfg(feature = "stats")]
+pub use stats::SortStats;
+pub use vec::{insert_sorted_by, VecSortBy, VecSortByExt};
+pub use watermark::{LatePolicy, Released, WatermarkSorted, WatermarkSorter};
+
+#[cfg(feature = "derive")]
+pub use sortby_derive::{DefaultSort, EnumOrder};
+
+pub(crate) enum IterState<I: Iterator> {
+    Unsorted(Option<Source<I>>),
+    Sorted(alloc::vec::IntoIter<I::Item>),
+}
+
+/// The items of a sort that has not happened yet: either still the source
+/// iterator, or already collected, e.g. by [`SortBy::peek_min`].
+pub(crate) enum Source<I: Iterator> {
+    Iter(I),
+    Buffered(alloc::vec::IntoIter<I::Item>),
+}
+
+impl<I: Iterator> Iterator for Source<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Source::Iter(iter) => iter.next(),
+            Source::Buffered(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Source::Iter(iter) => iter.size_hint(),
+            Source::Buffered(iter) => iter.size_hint(),
+        }
+    }
+}
 
-use std::cmp::Ordering;
+impl<I> Clone for Source<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Source::Iter(iter) => Source::Iter(iter.clone()),
+            Source::Buffered(iter) => Source::Buffered(iter.clone()),
+        }
+    }
+}
 
-enum IterState<I: Iterator> {
-    Unsorted(Option<I>),
-    Sorted(std::vec::IntoIter<I::Item>),
+impl<I> Clone for IterState<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            IterState::Unsorted(iter) => IterState::Unsorted(iter.clone()),
+            IterState::Sorted(iter) => IterState::Sorted(iter.clone()),
+        }
+    }
 }
 
 impl<I: Iterator> IterState<I> {
-    fn unwrap_sorted(&mut self) -> &mut std::vec::IntoIter<I::Item> {
+    fn unwrap_sorted(&mut self) -> &mut alloc::vec::IntoIter<I::Item> {
         match self {
             IterState::Unsorted(_) => panic!("unsorted"),
             IterState::Sorted(ref mut iter) => iter,
@@ -53,152 +258,2375 @@ impl<I: Iterator> IterState<I> {
     }
 }
 
-pub type CompareFn<'a, T> = Box<dyn Fn(&T, &T) -> Ordering + 'a>;
+/// A shared comparator. It is `Send` and `Sync` so sorts can be moved to or
+/// shared with other threads whenever the items and source iterator allow it,
+/// and reference counted so cloning a sort does not rebuild the chain.
+pub type CompareFn<'a, T> = Arc<dyn Fn(&T, &T) -> Ordering + Send + Sync + 'a>;
+
+/// The comparator of [`SortByIteratorExt::sort_default`].
+pub type DefaultSortFn<T> = fn(&T, &T) -> Ordering;
+
+pub(crate) fn compare_by<'a, T, F, V>(f: F) -> CompareFn<'a, T>
+where
+    V: PartialOrd,
+    F: Fn(&T) -> V + Send + Sync + 'a,
+{
+    Arc::new(move |a, b| f(a).partial_cmp(&f(b)).unwrap_or(Ordering::Equal))
+}
+
+pub(crate) fn compare_by_desc<'a, T, F, V>(f: F) -> CompareFn<'a, T>
+where
+    V: PartialOrd,
+    F: Fn(&T) -> V + Send + Sync + 'a,
+{
+    Arc::new(move |a, b| f(b).partial_cmp(&f(a)).unwrap_or(Ordering::Equal))
+}
+
+pub(crate) fn then_compare_by<'a, T, F, V>(prev: CompareFn<'a, T>, f: F) -> CompareFn<'a, T>
+where
+    T: 'a,
+    V: PartialOrd,
+    F: Fn(&T) -> V + Send + Sync + 'a,
+{
+    Arc::new(move |a, b| match (prev)(a, b) {
+        Ordering::Less => Ordering::Less,
+        Ordering::Greater => Ordering::Greater,
+        Ordering::Equal => f(a).partial_cmp(&f(b)).unwrap_or(Ordering::Equal),
+    })
+}
+
+pub(crate) fn then_compare_by_desc<'a, T, F, V>(prev: CompareFn<'a, T>, f: F) -> CompareFn<'a, T>
+where
+    T: 'a,
+    V: PartialOrd,
+    F: Fn(&T) -> V + Send + Sync + 'a,
+{
+    Arc::new(move |a, b| match (prev)(a, b) {
+        Ordering::Less => Ordering::Less,
+        Ordering::Greater => Ordering::Greater,
+        Ordering::Equal => f(b).partial_cmp(&f(a)).unwrap_or(Ordering::Equal),
+    })
+}
 
-pub struct SortBy<'a, I: Iterator> {
+/// A lazily sorted iterator. The comparator `C` is the concrete chain of
+/// sort levels; [`boxed`](SortBy::boxed) turns it into the default, boxed
+/// [`CompareFn`] when the type has to be named or stored.
+pub struct SortBy<'a, I: Iterator, C = CompareFn<'a, <I as Iterator>::Item>> {
     iter: IterState<I>,
-    compare: CompareFn<'a, I::Item>,
+    compare: C,
+    capacity: Option<usize>,
+    newest_first: bool,
+    strategy: Strategy,
+    check_consistency: bool,
+    plan: SortPlan,
+    observer: Option<&'a (dyn SortObserver + Sync)>,
+    #[cfg(feature = "stats")]
+    stats: stats::Counters,
+    /// Whether `next()` yielded an item.
+    yielded: bool,
+    lifetime: PhantomData<&'a ()>,
 }
 
-impl<'a, I> SortBy<'a, I>
+impl<'a, I, C> SortBy<'a, I, C>
 where
     I: Iterator,
+    C: Compare<I::Item>,
 {
-    pub fn then_sort_by<G, U>(self, f: G) -> SortBy<'a, I>
+    pub(crate) fn new(iter: I, compare: C, direction: Direction) -> SortBy<'a, I, C> {
+        let mut plan = SortPlan::default();
+        plan.push(direction);
+        SortBy {
+            iter: IterState::Unsorted(Some(Source::Iter(iter))),
+            compare,
+            capacity: None,
+            newest_first: false,
+            strategy: Strategy::Stable,
+            check_consistency: false,
+            plan,
+            observer: None,
+            #[cfg(feature = "stats")]
+            stats: Default::default(),
+            yielded: false,
+            lifetime: PhantomData,
+        }
+    }
+
+    pub fn then_sort_by<G, U>(self, f: G) -> SortBy<'a, I, Then<C, ByKey<G, U>>>
     where
         U: PartialOrd,
-        G: Fn(&I::Item) -> U + 'a,
-        Self: Sized,
-        <I as std::iter::Iterator>::Item: 'a,
+        G: Fn(&I::Item) -> U,
+    {
+        self.then_with(ByKey::new(f), Direction::Ascending)
+    }
+
+    pub fn then_sort_by_desc<G, U>(self, f: G) -> SortBy<'a, I, Then<C, ByKeyDesc<G, U>>>
+    where
+        U: PartialOrd,
+        G: Fn(&I::Item) -> U,
+    {
+        self.then_with(ByKeyDesc::new(f), Direction::Descending)
+    }
+
+    /// Breaks ties by an enum key in the explicit variant order of `order`,
+    /// see [`EnumOrder`](struct@EnumOrder).
+    pub fn then_sort_by_enum_order<G, E>(
+        self,
+        f: G,
+        order: &'a EnumOrder<E>,
+    ) -> SortBy<'a, I, Then<C, ByEnumOrder<'a, G, E>>>
+    where
+        G: Fn(&I::Item) -> &E,
+    {
+        self.then_with(ByEnumOrder::new(f, order), Direction::Ascending)
+    }
+
+    /// Breaks ties by a string key using the locale rules of `collator`, see
+    /// [`collate`]. Requires the `icu` feature.
+    #[cfg(feature = "icu")]
+    pub fn then_sort_by_collated<G>(
+        self,
+        f: G,
+        collator: &'a collate::Collator,
+    ) -> SortBy<'a, I, Then<C, collate::ByCollatedKey<'a, G>>>
+    where
+        G: Fn(&I::Item) -> &str,
+    {
+        self.then_with(
+            collate::ByCollatedKey::new(f, collator),
+            Direction::Ascending,
+        )
+    }
+
+    /// Breaks ties by a parsed regex capture group, see
+    /// [`SortByIteratorExt::sort_by_regex_capture`]. Requires the `regex`
+    /// feature.
+    #[cfg(feature = "regex")]
+    pub fn then_sort_by_regex_capture<P, K>(
+        self,
+        pattern: &'a regex::Regex,
+        group: usize,
+        parse: P,
+    ) -> SortBy<'a, I, Then<C, ByRegexCapture<'a, P, K>>>
+    where
+        I::Item: AsRef<str>,
+        P: Fn(&str) -> K,
+        K: PartialOrd,
+    {
+        self.then_with(
+            ByRegexCapture::new(pattern, group, parse),
+            Direction::Ascending,
+        )
+    }
+
+    /// Adds a level only if `cond` holds, e.g. for a tie-break behind a
+    /// feature flag, without branching around the rest of the chain.
+    pub fn then_sort_by_if<G, U>(
+        self,
+        cond: bool,
+        f: G,
+    ) -> SortBy<'a, I, Then<C, Option<ByKey<G, U>>>>
+    where
+        U: PartialOrd,
+        G: Fn(&I::Item) -> U,
+    {
+        let mut sort = self.then_with(cond.then(|| ByKey::new(f)), Direction::Ascending);
+        if !cond {
+            sort.plan.levels.pop();
+        }
+        sort
+    }
+
+    pub fn then_sort_by_desc_if<G, U>(
+        self,
+        cond: bool,
+        f: G,
+    ) -> SortBy<'a, I, Then<C, Option<ByKeyDesc<G, U>>>>
+    where
+        U: PartialOrd,
+        G: Fn(&I::Item) -> U,
+    {
+        let mut sort = self.then_with(cond.then(|| ByKeyDesc::new(f)), Direction::Descending);
+        if !cond {
+            sort.plan.levels.pop();
+        }
+        sort
+    }
+
+    /// Adds the levels of `spec`, e.g. an optional user preference, or none
+    /// if it is `None`. `field` resolves field names like in
+    /// [`SortSpec::compare_fn`].
+    #[allow(clippy::type_complexity)]
+    pub fn then_sort_by_spec<'s, F>(
+        self,
+        spec: impl Into<Option<&'s SortSpec>>,
+        field: F,
+    ) -> Result<SortBy<'a, I, Then<C, Option<CompareFn<'a, I::Item>>>>, SortError>
+    where
+        I::Item: 'a,
+        F: FnMut(&str) -> Option<CompareFn<'a, I::Item>>,
     {
-        let prev = self.compare;
+        let spec = spec.into();
+        let compare = spec.map(|spec| spec.compare_fn(field)).transpose()?;
+        let mut sort = self.then_with(compare, Direction::Ascending);
+        sort.plan.levels.pop();
+        for key in spec.iter().flat_map(|spec| &spec.keys) {
+            sort.plan.push(key.direction);
+        }
+        Ok(sort)
+    }
+
+    fn then_with<D>(self, next: D, direction: Direction) -> SortBy<'a, I, Then<C, D>> {
+        let mut plan = self.plan;
+        plan.push(direction);
         SortBy {
             iter: self.iter,
-            compare: Box::new(move |a, b| match (prev)(a, b) {
-                Ordering::Less => Ordering::Less,
-                Ordering::Greater => Ordering::Greater,
-                Ordering::Equal => f(a).partial_cmp(&f(b)).unwrap_or(Ordering::Equal),
-            }),
+            compare: Then::new(self.compare, next),
+            capacity: self.capacity,
+            newest_first: self.newest_first,
+            strategy: self.strategy,
+            check_consistency: self.check_consistency,
+            plan,
+            observer: self.observer,
+            #[cfg(feature = "stats")]
+            stats: Default::default(),
+            yielded: self.yielded,
+            lifetime: PhantomData,
         }
     }
 
-    pub fn then_sort_by_desc<G, U>(self, f: G) -> SortBy<'a, I>
+    /// Gives up the stable order of equal items for a faster sort. Adaptors
+    /// that break ties by input position explicitly, like [`page`], are not
+    /// affected.
+    ///
+    /// [`page`]: SortBy::page
+    pub fn unstable(mut self) -> SortBy<'a, I, C> {
+        self.strategy = Strategy::Unstable;
+        self
+    }
+
+    /// Whether equal items keep their input order, which is the default.
+    pub fn is_stable(&self) -> bool {
+        self.strategy == Strategy::Stable
+    }
+
+    /// Sorts an iterator of references and returns owned clones of the items
+    /// in order, like [`Iterator::cloned`]. The items are sorted right away
+    /// and the result is a `vec::IntoIter`, so collecting it into a `Vec`
+    /// reuses its buffer.
+    pub fn sorted_cloned<'b, T>(mut self) -> alloc::vec::IntoIter<T>
     where
-        U: PartialOrd,
-        G: Fn(&I::Item) -> U + 'a,
-        Self: Sized,
-        <I as std::iter::Iterator>::Item: 'a,
+        I: Iterator<Item = &'b T>,
+        T: Clone + 'b,
+    {
+        let items: Vec<T> = self.take_vec().into_iter().cloned().collect();
+        items.into_iter()
+    }
+
+    /// Like [`sorted_cloned`](SortBy::sorted_cloned), but copies the items,
+    /// like [`Iterator::copied`].
+    pub fn sorted_copied<'b, T>(mut self) -> alloc::vec::IntoIter<T>
+    where
+        I: Iterator<Item = &'b T>,
+        T: Copy + 'b,
+    {
+        let items: Vec<T> = self.take_vec().into_iter().copied().collect();
+        items.into_iter()
+    }
+
+    /// Spot-checks the composed comparator before sorting, in debug builds
+    /// only, and panics with the input positions of the offending items if it
+    /// is not a total order, e.g. because keys contain NaN, which would
+    /// otherwise produce a silently wrong order.
+    ///
+    /// Every pair and triple among up to 32 evenly spaced items is checked for
+    /// antisymmetry and transitivity, so a violation between other items can
+    /// go unnoticed.
+    pub fn check_consistency(mut self) -> SortBy<'a, I, C> {
+        self.check_consistency = true;
+        self
+    }
+
+    /// Reports the phases of this sort to `observer`, in addition to the
+    /// global observer, see [`SortObserver`].
+    pub fn observe(mut self, observer: &'a (dyn SortObserver + Sync)) -> SortBy<'a, I, C> {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Recovers the source iterator and the comparator, e.g. to skip sorting
+    /// at runtime. Fails and hands the sort back once the iterator has been
+    /// drained, which happens on the first `next()` or [`peek_min`].
+    ///
+    /// [`peek_min`]: SortBy::peek_min
+    pub fn into_inner(mut self) -> Result<(I, C), SortBy<'a, I, C>> {
+        match self.iter {
+            IterState::Unsorted(ref mut iter @ Some(Source::Iter(_))) => match iter.take() {
+                Some(Source::Iter(iter)) => Ok((iter, self.compare)),
+                _ => unreachable!(),
+            },
+            _ => Err(self),
+        }
+    }
+
+    /// Returns the item the sort would yield first without sorting. The items
+    /// are collected on the first call and scanned in O(n); they are only
+    /// sorted once iteration starts.
+    pub fn peek_min(&mut self) -> Option<&I::Item> {
+        self.buffer_source();
+        match self.iter {
+            IterState::Unsorted(Some(Source::Buffered(ref iter))) => {
+                let items = iter.as_slice();
+                let mut min = items.first()?;
+                for item in &items[1..] {
+                    match self.comparator().compare(item, min) {
+                        Ordering::Less => min = item,
+                        Ordering::Equal if self.newest_first => min = item,
+                        _ => {}
+                    }
+                }
+                Some(min)
+            }
+            IterState::Sorted(ref iter) => iter.as_slice().first(),
+            IterState::Unsorted(_) => unreachable!(),
+        }
+    }
+
+    /// Finds the longest run of items that is already in order under the
+    /// composed comparator, e.g. to choose between merging new items into a
+    /// cache and a full re-sort. Like [`peek_min`](SortBy::peek_min), this
+    /// buffers the items without sorting them. On a sort that already started
+    /// yielding, the remaining items are considered.
+    pub fn longest_run(&mut self) -> Run {
+        self.buffer_source();
+        let items = match self.iter {
+            IterState::Unsorted(Some(Source::Buffered(ref iter))) => iter.as_slice(),
+            IterState::Sorted(ref iter) => iter.as_slice(),
+            IterState::Unsorted(_) => unreachable!(),
+        };
+
+        let mut longest = Run {
+            start: 0,
+            len: items.len().min(1),
+        };
+        let mut start = 0;
+        for i in 1..items.len() {
+            if self.comparator().compare(&items[i - 1], &items[i]) == Ordering::Greater {
+                start = i;
+            }
+            if i + 1 - start > longest.len {
+                longest = Run {
+                    start,
+                    len: i + 1 - start,
+                };
+            }
+        }
+        longest
+    }
+
+    /// Breaks the remaining ties by reverse input order, so among equal items
+    /// the one that came last wins, instead of the default oldest first. This
+    /// also holds for [`unstable`](SortBy::unstable) sorts and chunks.
+    pub fn then_sort_by_input_order_desc(mut self) -> SortBy<'a, I, C> {
+        self.newest_first = true;
+        self.plan.push(Direction::Descending);
+        self.plan.name_last("input order");
+        self
+    }
+
+    /// Replaces the comparator with one built from the current chain, e.g. to
+    /// add logging, invert it conditionally or apply a custom tie policy.
+    pub fn map_compare<M, F>(self, f: M) -> SortBy<'a, I, FnCompare<F>>
+    where
+        M: FnOnce(C) -> F,
+        F: Fn(&I::Item, &I::Item) -> Ordering,
     {
-        let prev = self.compare;
         SortBy {
             iter: self.iter,
-            compare: Box::new(move |a, b| match (prev)(a, b) {
-                Ordering::Less => Ordering::Less,
-                Ordering::Greater => Ordering::Greater,
-                Ordering::Equal => f(b).partial_cmp(&f(a)).unwrap_or(Ordering::Equal),
-            }),
+            compare: FnCompare(f(self.compare)),
+            capacity: self.capacity,
+            newest_first: self.newest_first,
+            strategy: self.strategy,
+            check_consistency: self.check_consistency,
+            plan: self.plan,
+            observer: self.observer,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+            yielded: self.yielded,
+            lifetime: PhantomData,
         }
     }
-}
 
-impl<'a, I> From<SortBy<'a, I>> for Vec<I::Item>
-where
-    I: Iterator,
-{
-    fn from(mut val: SortBy<'a, I>) -> Self {
-        match val.iter {
+    /// Erases the comparator chain into a boxed [`CompareFn`], e.g. to store
+    /// sorts with differently composed levels side by side.
+    pub fn boxed(self) -> SortBy<'a, I>
+    where
+        C: Send + Sync + 'a,
+    {
+        SortBy {
+            iter: self.iter,
+            compare: self.compare.into_compare_fn(),
+            capacity: self.capacity,
+            newest_first: self.newest_first,
+            strategy: self.strategy,
+            check_consistency: self.check_consistency,
+            plan: self.plan,
+            observer: self.observer,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+            yielded: self.yielded,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Borrows the composed comparator, e.g. for `binary_search_by` on a
+    /// slice sorted the same way.
+    pub fn compare_fn(&self) -> impl Fn(&I::Item, &I::Item) -> Ordering + '_ {
+        move |a, b| self.compare.compare(a, b)
+    }
+
+    /// Consumes the sort and returns its composed comparator.
+    pub fn into_compare(self) -> impl Fn(&I::Item, &I::Item) -> Ordering {
+        let compare = self.compare;
+        move |a, b| compare.compare(a, b)
+    }
+
+    /// Names the most recently added sort level for [`describe`](SortBy::describe).
+    pub fn named(mut self, name: &'static str) -> SortBy<'a, I, C> {
+        self.plan.name_last(name);
+        self
+    }
+
+    /// Describes the composed sort levels, e.g. to check which ordering was
+    /// actually applied to a report.
+    pub fn describe(&self) -> &SortPlan {
+        &self.plan
+    }
+
+    /// Returns the comparisons and key extractions counted so far. Requires
+    /// the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> SortStats {
+        self.stats.get()
+    }
+
+    /// Pre-allocates the internal buffer for at least `n` items. The
+    /// iterator's `size_hint` is used as well, whichever is larger.
+    pub fn with_capacity_hint(mut self, n: usize) -> SortBy<'a, I, C> {
+        self.capacity = Some(n);
+        self
+    }
+
+    /// Guards against unbounded sources by reading at most `n` items, plus one
+    /// to detect an overflow, which is then handled according to `overflow`.
+    ///
+    /// The items are collected and sorted right away, so call this after all
+    /// sort levels have been chained.
+    pub fn max_items(
+        mut self,
+        n: usize,
+        overflow: Overflow,
+    ) -> Result<alloc::vec::IntoIter<I::Item>, SortError> {
+        let (mut vec, sorted) = match self.iter {
             IterState::Unsorted(ref mut iter) => {
-                let mut vec: Vec<_> = iter.take().unwrap().collect();
-                vec.sort_by(|a, b| (val.compare)(a, b));
-                vec
+                let iter = iter.take().unwrap();
+                (
+                    self.collect_with_capacity(iter.take(n.saturating_add(1))),
+                    false,
+                )
             }
-            IterState::Sorted(iter) => {
-                // this is a bit of a weird edge case ... the iterator already
-                // yieled at least one item ... return the remainder as a new
-                // `Vec` seems to be the most sensible behavior
-                iter.collect()
+            IterState::Sorted(ref mut iter) => (
+                core::mem::replace(iter, Vec::new().into_iter()).collect(),
+                true,
+            ),
+        };
+
+        if vec.len() > n {
+            match overflow {
+                Overflow::Error => return Err(SortError::LimitExceeded { limit: n }),
+                Overflow::Truncate => vec.truncate(n),
             }
         }
+        if !sorted {
+            self.sort_items(&mut vec);
+        }
+        Ok(vec.into_iter())
     }
-}
 
-impl<'a, I> Iterator for SortBy<'a, I>
-where
-    I: Iterator,
-{
-    type Item = I::Item;
+    /// Sorts the items into a `Vec`. This is the plain terminator; it does
+    /// the same as `collect::<Vec<_>>()`, without reallocating. The name
+    /// `into_sorted_vec` is taken by the [`SortedVec`] terminator.
+    ///
+    /// On a sort that already yielded items, e.g. through `next()`, only the
+    /// remaining items are returned, in order. `Vec::try_from` fails on such
+    /// a sort instead, see [`has_started`](SortBy::has_started).
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let mut sort = vec![3, 1, 2].into_iter().sort_by(|v| *v);
+    /// assert!(!sort.has_started());
+    /// assert_eq!(sort.next(), Some(1));
+    /// assert!(sort.has_started());
+    /// assert_eq!(sort.into_vec(), vec![2, 3]);
+    /// ```
+    pub fn into_vec(mut self) -> Vec<I::Item> {
+        self.take_vec()
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Sorts the items into a `VecDeque`, e.g. to take items from both ends
+    /// of the order.
+    pub fn into_sorted_deque(mut self) -> alloc::collections::VecDeque<I::Item> {
+        self.take_vec().into()
+    }
+
+    /// Sorts the items into a [`SortedHeap`] that keeps the composed
+    /// comparator, so items can be pushed afterwards and still pop in order.
+    pub fn into_binary_heap(mut self) -> SortedHeap<'a, I::Item>
+    where
+        C: Send + Sync + 'a,
+    {
+        let items = self.take_vec();
+        SortedHeap::from_sorted(items, self.compare.into_compare_fn())
+    }
+
+    /// Sorts the items into a [`SortedCache`] that hands out any number of
+    /// iterators over the same order.
+    pub fn into_cached(mut self) -> SortedCache<I::Item> {
+        SortedCache::new(self.take_vec().into())
+    }
+
+    /// Sorts the items and splits them into those matching `pred` and the
+    /// rest, in one pass over the sorted buffer. Both keep the sort order.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let rows = vec![(3, true), (1, false), (2, true), (0, true)];
+    /// let (active, archived) = rows.into_iter().sort_by(|v| v.0).partition_sorted(|v| v.1);
+    /// assert_eq!(active, vec![(0, true), (2, true), (3, true)]);
+    /// assert_eq!(archived, vec![(1, false)]);
+    /// ```
+    pub fn partition_sorted<P>(mut self, pred: P) -> (Vec<I::Item>, Vec<I::Item>)
+    where
+        P: FnMut(&I::Item) -> bool,
+    {
+        self.take_vec().into_iter().partition(pred)
+    }
+
+    /// Whether `next()` yielded an item already. Those items are missing
+    /// from the terminators, and `Vec::try_from` fails. Buffering the items, e.g. with
+    /// [`peek_min`](SortBy::peek_min) or [`longest_run`](SortBy::longest_run),
+    /// does not count.
+    pub fn has_started(&self) -> bool {
+        self.yielded
+    }
+
+    /// Sorts the items into a [`SortedVec`] that keeps the composed
+    /// comparator around for searching and inserting.
+    pub fn into_sorted_vec(mut self) -> SortedVec<'a, I::Item>
+    where
+        C: Send + Sync + 'a,
+    {
+        let items = self.take_vec();
+        SortedVec::from_sorted(items, self.compare.into_compare_fn())
+    }
+
+    /// Like [`into_sorted_vec`](SortBy::into_sorted_vec), but fails with
+    /// [`SortError::AllocationFailed`] instead of aborting when the buffers
+    /// cannot be allocated.
+    ///
+    /// The items are sorted in place with an unstable sort that breaks ties by
+    /// input position, so the sort itself never allocates a scratch buffer.
+    pub fn try_into_sorted_vec(mut self) -> Result<SortedVec<'a, I::Item>, SortError>
+    where
+        C: Send + Sync + 'a,
+    {
+        let items = match self.iter {
+            IterState::Unsorted(ref mut iter) => {
+                let iter = iter.take().unwrap();
+                let mut vec = self.try_collect_with_capacity(iter.enumerate())?;
+                let _span = trace::sort(&vec).observed_by(self.observer);
+                let compare = self.comparator();
+                vec.sort_unstable_by(|a, b| {
+                    compare.compare(&a.1, &b.1).then(self.tie_break(a.0, b.0))
+                });
+
+                let mut items = Vec::new();
+                items.try_reserve_exact(vec.len())?;
+                items.extend(vec.into_iter().map(|(_, item)| item));
+                items
+            }
+            IterState::Sorted(ref mut iter) => {
+                let iter = core::mem::replace(iter, Vec::new().into_iter());
+                self.try_collect_with_capacity(iter)?
+            }
+        };
+        Ok(SortedVec::from_sorted(
+            items,
+            self.compare.into_compare_fn(),
+        ))
+    }
+
+    /// Sorts the items into a `Vec` that lives in `alloc`, e.g. an arena or
+    /// bump allocator. Requires the nightly-only `allocator_api` feature.
+    ///
+    /// Like [`try_into_sorted_vec`](SortBy::try_into_sorted_vec), this sorts
+    /// with an unstable sort that breaks ties by input position, so every
+    /// buffer comes from `alloc` and none from the global allocator.
+    #[cfg(feature = "allocator_api")]
+    pub fn into_vec_in<A>(mut self, alloc: A) -> Vec<I::Item, A>
+    where
+        A: Allocator + Clone,
+    {
         match self.iter {
             IterState::Unsorted(ref mut iter) => {
-                let mut vec: Vec<_> = iter.take().unwrap().collect();
-                vec.sort_by(|a, b| (self.compare)(a, b));
-                self.iter = IterState::Sorted(vec.into_iter());
-                self.iter.unwrap_sorted().next()
+                let iter = iter.take().unwrap();
+                let (lower, _) = iter.size_hint();
+                let capacity = self.capacity.unwrap_or(0).max(lower);
+                let mut vec = Vec::with_capacity_in(capacity, alloc.clone());
+                vec.extend(iter.enumerate());
+                let compare = self.comparator();
+                vec.sort_unstable_by(|a: &(usize, I::Item), b| {
+                    compare.compare(&a.1, &b.1).then(self.tie_break(a.0, b.0))
+                });
+
+                let mut items = Vec::with_capacity_in(vec.len(), alloc);
+                items.extend(vec.into_iter().map(|(_, item)| item));
+                items
+            }
+            IterState::Sorted(ref mut iter) => {
+                let mut items = Vec::new_in(alloc);
+                items.extend(iter);
+                items
             }
-            IterState::Sorted(ref mut iter) => iter.next(),
         }
     }
-}
 
-pub trait SortByIteratorExt: Iterator {
-    fn sort_by<'a, F, V>(self, f: F) -> SortBy<'a, Self>
+    /// Sorts exactly `N` items into an array without allocating, e.g. for
+    /// const-generic pipelines. Fails if the iterator yields fewer or more
+    /// items, reading at most one past `N`.
+    ///
+    /// Sorts with the same binary insertion sort as
+    /// [`SortByIteratorExt::sort_by_into_array`], so it suits small `N` only.
+    pub fn into_sorted_array<const N: usize>(mut self) -> Result<[I::Item; N], SortError> {
+        let iter = core::mem::replace(&mut self.iter, IterState::Unsorted(None));
+        let compare = self.comparator();
+        match iter {
+            IterState::Unsorted(iter) => {
+                array::sort_into_array(iter.unwrap(), &compare, self.newest_first)
+            }
+            IterState::Sorted(iter) => array::sort_into_array(iter, &compare, false),
+        }
+    }
+
+    /// Sorts the items into a [`SmallVec`] that keeps up to `N` items inline.
+    /// Requires the `smallvec` feature.
+    ///
+    /// The items are sorted in place with an unstable sort that breaks ties by
+    /// input position, which keeps the order stable without a scratch buffer,
+    /// so at most `N` items never touch the heap.
+    #[cfg(feature = "smallvec")]
+    pub fn into_sorted_smallvec<const N: usize>(mut self) -> SmallVec<[I::Item; N]> {
+        match self.iter {
+            IterState::Unsorted(ref mut iter) => {
+                let iter = iter.take().unwrap();
+                let mut vec: SmallVec<[(usize, I::Item); N]> = {
+                    let mut span = trace::collect::<I::Item>().observed_by(self.observer);
+                    let vec: SmallVec<_> = iter.enumerate().collect();
+                    span.record_items(vec.len());
+                    vec
+                };
+
+                let _span = trace::sort(&vec).observed_by(self.observer);
+                let compare = self.comparator();
+                vec.sort_unstable_by(|a, b| {
+                    compare.compare(&a.1, &b.1).then(self.tie_break(a.0, b.0))
+                });
+                vec.into_iter().map(|(_, item)| item).collect()
+            }
+            IterState::Sorted(ref mut iter) => iter.collect(),
+        }
+    }
+
+    /// Collects the sorted items into an [`IndexMap`] keyed by `key`, which
+    /// keeps the sort order for iteration while allowing lookups by key.
+    /// Requires the `indexmap` feature.
+    ///
+    /// If several items share a key, the map keeps the position of the first
+    /// one and the value of the last one.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let users = vec![("carol", 31), ("alice", 42), ("bob", 27)];
+    /// let by_age = users.into_iter().sort_by(|u| u.1).into_index_map(|u| u.0);
+    /// assert_eq!(by_age.keys().collect::<Vec<_>>(), vec![&"bob", &"carol", &"alice"]);
+    /// assert_eq!(by_age["alice"], ("alice", 42));
+    /// ```
+    #[cfg(feature = "indexmap")]
+    pub fn into_index_map<K, F>(self, key: F) -> IndexMap<K, I::Item>
     where
-        V: PartialOrd,
-        F: Fn(&Self::Item) -> V + 'a,
-        Self: Sized,
+        K: core::hash::Hash + Eq,
+        F: Fn(&I::Item) -> K,
     {
-        SortBy {
-            iter: IterState::Unsorted(Some(self)),
-            compare: Box::new(move |a, b| f(a).partial_cmp(&f(b)).unwrap_or(Ordering::Equal)),
+        self.map(|item| (key(&item), item)).collect()
+    }
+
+    /// Returns the items of the zero-based page `page` with `per_page` items
+    /// each, in the same order a full sort would produce.
+    ///
+    /// Only the items of the requested page are fully sorted, everything else
+    /// is just partitioned around it.
+    pub fn page(mut self, page: usize, per_page: usize) -> Vec<I::Item> {
+        let start = page.saturating_mul(per_page);
+        let iter = match self.iter {
+            IterState::Unsorted(ref mut iter) => iter.take().unwrap(),
+            IterState::Sorted(ref mut iter) => {
+                return iter.skip(start).take(per_page).collect();
+            }
+        };
+
+        let mut vec = self.collect_with_capacity(iter.enumerate());
+        if start >= vec.len() {
+            return Vec::new();
         }
+        let end = start.saturating_add(per_page).min(vec.len());
+
+        // ties are broken by input position so the unstable selection yields
+        // the same page as the stable full sort
+        let _span = trace::sort(&vec).observed_by(self.observer);
+        let compare = self.comparator();
+        let compare = |a: &(usize, I::Item), b: &(usize, I::Item)| {
+            compare.compare(&a.1, &b.1).then(self.tie_break(a.0, b.0))
+        };
+        if start > 0 {
+            vec.select_nth_unstable_by(start, compare);
+        }
+        let window = &mut vec[start..];
+        if end - start < window.len() {
+            window.select_nth_unstable_by(end - start, compare);
+        }
+        window[..end - start].sort_unstable_by(compare);
+
+        vec.drain(start..end).map(|(_, item)| item).collect()
     }
 
-    fn sort_by_desc<'a, F, V>(self, f: F) -> SortBy<'a, Self>
+    /// Continues after an item with key `boundary`, e.g. the last item of a
+    /// previously returned page. `key` must follow the sort order, see
+    /// [`SortedVec::skip_after_key`].
+    ///
+    /// The items are sorted right away, so call this after all sort levels
+    /// have been chained.
+    pub fn skip_after_key<K, F>(mut self, boundary: &K, key: F) -> alloc::vec::IntoIter<I::Item>
     where
-        V: PartialOrd,
-        F: Fn(&Self::Item) -> V + 'a,
-        Self: Sized,
+        K: PartialOrd,
+        F: Fn(&I::Item) -> K,
     {
-        SortBy {
-            iter: IterState::Unsorted(Some(self)),
-            compare: Box::new(move |a, b| f(b).partial_cmp(&f(a)).unwrap_or(Ordering::Equal)),
+        let vec = self.take_vec();
+        let skip = sorted_vec::skip_after_key_index(&vec, boundary, &key);
+        let mut iter = vec.into_iter();
+        if skip > 0 {
+            iter.nth(skip - 1);
         }
+        iter
     }
-}
 
-impl<T: ?Sized> SortByIteratorExt for T where T: Iterator {}
+    /// Stops once the sorted items pass `bound` on `key`, like "everything
+    /// with a score of at least 0.8, best first". Whether passing means going
+    /// above or below `bound` follows from the direction `key` is sorted in.
+    ///
+    /// Items beyond the bound are dropped before sorting, so they are never
+    /// ordered. The rest are sorted right away, so call this after all sort
+    /// levels have been chained.
+    pub fn take_until_key<K, F>(mut self, bound: Bound<K>, key: F) -> alloc::vec::IntoIter<I::Item>
+    where
+        K: PartialOrd,
+        F: Fn(&I::Item) -> K,
+    {
+        let within = |item: &I::Item, descending: bool| match (&bound, descending) {
+            (Bound::Included(bound), false) => key(item) <= *bound,
+            (Bound::Excluded(bound), false) => key(item) < *bound,
+            (Bound::Included(bound), true) => key(item) >= *bound,
+            (Bound::Excluded(bound), true) => key(item) > *bound,
+            (Bound::Unbounded, _) => true,
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let vec = match self.iter {
+            IterState::Unsorted(ref mut iter) => {
+                let iter = iter.take().unwrap();
+                let mut vec = self.collect_with_capacity(iter);
 
-    #[derive(Clone, Debug, Eq, PartialEq)]
-    struct Person {
-        pub age: i32,
-        pub name: &'static str,
+                let first = vec.iter().min_by(|a, b| self.comparator().compare(a, b));
+                let last = vec.iter().max_by(|a, b| self.comparator().compare(a, b));
+                let descending = match (first, last) {
+                    (Some(first), Some(last)) => key(first) > key(last),
+                    _ => false,
+                };
+                vec.retain(|item| within(item, descending));
+                self.sort_items(&mut vec);
+                vec
+            }
+            IterState::Sorted(_) => {
+                let mut vec = self.take_vec();
+                let descending = match (vec.first(), vec.last()) {
+                    (Some(first), Some(last)) => key(first) > key(last),
+                    _ => false,
+                };
+                let end = vec.partition_point(|item| within(item, descending));
+                vec.truncate(end);
+                vec
+            }
+        };
+        vec.into_iter()
     }
 
-    #[test]
-    fn works_with_empty_iterators() {
-        let input = Vec::<i32>::new();
-        let actual: Vec<_> = input.into_iter().sort_by(|v| *v).collect();
+    /// Yields the sorted items paired with their percentile rank under the
+    /// composed comparator, from `0.0` for the first item to `1.0` for the
+    /// last, e.g. to feed normalized ranks into a scoring model.
+    ///
+    /// Items that compare equal share the average of their positions, so a
+    /// tie group spanning the whole input, or a single item, ranks `0.5`.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let ranks: Vec<_> = vec![30, 10, 20, 20].into_iter().sort_by(|v| *v).with_percentile_rank().collect();
+    /// assert_eq!(ranks, vec![(0.0, 10), (0.5, 20), (0.5, 20), (1.0, 30)]);
+    /// ```
+    pub fn with_percentile_rank(mut self) -> alloc::vec::IntoIter<(f64, I::Item)> {
+        let vec = self.take_vec();
+        let last = vec.len().saturating_sub(1) as f64;
+        let mut ranks = Vec::with_capacity(vec.len());
+        let mut start = 0;
+        for end in 1..=vec.len() {
+            if end < vec.len()
+                && self.comparator().compare(&vec[start], &vec[end]) == Ordering::Equal
+            {
+                continue;
+            }
+            let rank = if last > 0.0 {
+                (start + end - 1) as f64 / 2.0 / last
+            } else {
+                0.5
+            };
+            ranks.extend(core::iter::repeat_n(rank, end - start));
+            start = end;
+        }
+        ranks.into_iter().zip(vec).collect::<Vec<_>>().into_iter()
+    }
 
-        assert_equal(actual, vec![]);
+    /// Pairs each item with its dense rank, starting at 1: items that compare
+    /// equal share a rank, and the next distinct item gets the next rank, like
+    /// SQL's `DENSE_RANK`. The items are sorted right away.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let ranked: Vec<_> = vec![("b", 7), ("a", 9), ("c", 7), ("d", 3)]
+    ///     .into_iter()
+    ///     .sort_by_desc(|v| v.1)
+    ///     .enumerate_ranked()
+    ///     .map(|(rank, v)| (rank, v.0))
+    ///     .collect();
+    /// assert_eq!(ranked, vec![(1, "a"), (2, "b"), (2, "c"), (3, "d")]);
+    /// ```
+    pub fn enumerate_ranked(mut self) -> alloc::vec::IntoIter<(usize, I::Item)> {
+        let vec = self.take_vec();
+        let mut ranks = Vec::with_capacity(vec.len());
+        let mut rank = 0;
+        for i in 0..vec.len() {
+            if i == 0 || self.comparator().compare(&vec[i - 1], &vec[i]) != Ordering::Equal {
+                rank += 1;
+            }
+            ranks.push(rank);
+        }
+        ranks.into_iter().zip(vec).collect::<Vec<_>>().into_iter()
     }
 
-    #[test]
-    fn converts_into_vec_to_avoid_double_allocation() {
-        let input = vec![5, 2, 3];
-        let actual: Vec<_> = input.into_iter().sort_by(|v| *v).into();
+    /// Pairs each item with one of `k` equal-frequency buckets, `0..k` in sort
+    /// order, e.g. quartiles for `k = 4`. An item's bucket is its position
+    /// times `k / len`, except that items comparing equal all go to the
+    /// bucket of the first of them, so buckets can end up uneven or empty
+    /// when there are many ties. The items are sorted right away.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let quartiles: Vec<_> = vec![8, 1, 5, 3, 3, 7, 2, 6]
+    ///     .into_iter()
+    ///     .sort_by(|v| *v)
+    ///     .with_quantile_bucket(4)
+    ///     .collect();
+    /// assert_eq!(
+    ///     quartiles,
+    ///     vec![(0, 1), (0, 2), (1, 3), (1, 3), (2, 5), (2, 6), (3, 7), (3, 8)]
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    pub fn with_quantile_bucket(mut self, k: usize) -> alloc::vec::IntoIter<(usize, I::Item)> {
+        assert!(k > 0, "cannot split into zero buckets");
+        let vec = self.take_vec();
+        let len = vec.len();
+        let mut buckets = Vec::with_capacity(len);
+        let mut bucket = 0;
+        for i in 0..len {
+            if i == 0 || self.comparator().compare(&vec[i - 1], &vec[i]) != Ordering::Equal {
+                // u128 so `i * k` cannot overflow
+                bucket = (i as u128 * k as u128 / len as u128) as usize;
+            }
+            buckets.push(bucket);
+        }
+        buckets.into_iter().zip(vec).collect::<Vec<_>>().into_iter()
+    }
 
-        assert_equal(actual, vec![2, 3, 5]);
+    /// Keeps the items matching `is_pinned` out of the sort, see [`PinMode`]
+    /// for where they end up. The rest are sorted right away, so call this
+    /// after all sort levels have been chained.
+    pub fn with_pins<P>(mut self, is_pinned: P, mode: PinMode) -> alloc::vec::IntoIter<I::Item>
+    where
+        P: Fn(&I::Item) -> bool,
+    {
+        let (items, sorted) = match self.iter {
+            IterState::Unsorted(ref mut iter) => {
+                let iter = iter.take().unwrap();
+                (self.collect_with_capacity(iter), false)
+            }
+            IterState::Sorted(ref mut iter) => (iter.collect(), true),
+        };
+        let vec = pins::sort_with_pins(items, is_pinned, mode, |rest| {
+            if !sorted {
+                self.sort_items(rest);
+            }
+        });
+        vec.into_iter()
+    }
+
+    /// Orders the items by a key with few distinct values, e.g. country codes,
+    /// keeping the order of this sort among equal keys. Every distinct key is
+    /// ranked once and the sort then only compares the ranks.
+    ///
+    /// The items are sorted by the chained levels right away, so this has to
+    /// come after all of them.
+    pub fn sort_by_interned<K, F>(self, f: F) -> alloc::vec::IntoIter<I::Item>
+    where
+        K: Ord,
+        F: Fn(&I::Item) -> K,
+    {
+        self.sort_interned(f, false)
+    }
+
+    pub fn sort_by_interned_desc<K, F>(self, f: F) -> alloc::vec::IntoIter<I::Item>
+    where
+        K: Ord,
+        F: Fn(&I::Item) -> K,
+    {
+        self.sort_interned(f, true)
+    }
+
+    fn sort_interned<K, F>(mut self, f: F, descending: bool) -> alloc::vec::IntoIter<I::Item>
+    where
+        K: Ord,
+        F: Fn(&I::Item) -> K,
+    {
+        intern::sort_interned(self.take_vec(), f, descending).into_iter()
+    }
+
+    /// Orders the items by the value of the field `field` in their serde
+    /// serialization, e.g. to sort third-party structs by a field name
+    /// chosen at runtime, keeping the order of this sort among equal values.
+    /// Values are ordered like [`json::JsonOrder::new`]. Items without the
+    /// field, or that do not serialize to a struct or map, are treated as
+    /// `null`. Requires the `serde` feature.
+    ///
+    /// Only the field is serialized, once per item, but prefer a key function
+    /// where the field is known at compile time. The items are sorted by the
+    /// chained levels right away, so this has to come after all of them.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SortError::KeyExtractionFailed`] if the field of an item
+    /// cannot be serialized.
+    #[cfg(feature = "serde")]
+    pub fn sort_by_serde_field(
+        self,
+        field: &str,
+    ) -> Result<alloc::vec::IntoIter<I::Item>, SortError>
+    where
+        I::Item: serde::Serialize,
+    {
+        self.sort_serde_field(field, false)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn sort_by_serde_field_desc(
+        self,
+        field: &str,
+    ) -> Result<alloc::vec::IntoIter<I::Item>, SortError>
+    where
+        I::Item: serde::Serialize,
+    {
+        self.sort_serde_field(field, true)
+    }
+
+    #[cfg(feature = "serde")]
+    fn sort_serde_field(
+        mut self,
+        field: &str,
+        descending: bool,
+    ) -> Result<alloc::vec::IntoIter<I::Item>, SortError>
+    where
+        I::Item: serde::Serialize,
+    {
+        fields::sort_by_serde_field(self.take_vec(), field, descending).map(Vec::into_iter)
+    }
+
+    /// Orders whole groups of items, as determined by `group_key`, by an
+    /// aggregate over each group, e.g. threads by their most recent message.
+    /// Within a group, the items stay in the order of this sort, so the items
+    /// are sorted right away and this has to come after all sort levels.
+    #[cfg(feature = "std")]
+    pub fn sort_groups_by_aggregate<K, A, G, F>(
+        self,
+        group_key: G,
+        aggregate: F,
+    ) -> alloc::vec::IntoIter<I::Item>
+    where
+        K: Eq + core::hash::Hash,
+        A: PartialOrd,
+        G: Fn(&I::Item) -> K,
+        F: Fn(&[I::Item]) -> A,
+    {
+        self.sort_groups(group_key, aggregate, false)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn sort_groups_by_aggregate_desc<K, A, G, F>(
+        self,
+        group_key: G,
+        aggregate: F,
+    ) -> alloc::vec::IntoIter<I::Item>
+    where
+        K: Eq + core::hash::Hash,
+        A: PartialOrd,
+        G: Fn(&I::Item) -> K,
+        F: Fn(&[I::Item]) -> A,
+    {
+        self.sort_groups(group_key, aggregate, true)
+    }
+
+    #[cfg(feature = "std")]
+    fn sort_groups<K, A, G, F>(
+        mut self,
+        group_key: G,
+        aggregate: F,
+        descending: bool,
+    ) -> alloc::vec::IntoIter<I::Item>
+    where
+        K: Eq + core::hash::Hash,
+        A: PartialOrd,
+        G: Fn(&I::Item) -> K,
+        F: Fn(&[I::Item]) -> A,
+    {
+        let items = self.take_vec();
+        groups::sort_groups(items, group_key, aggregate, descending).into_iter()
+    }
+
+    /// Sorts every consecutive chunk of `n` items on its own instead of the
+    /// whole iterator, so at most `n` items are buffered at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn sort_within_chunks(self, n: usize) -> SortedChunks<'a, I, C> {
+        assert!(n != 0, "chunk size must be non-zero");
+        SortedChunks::new(self.iter, self.compare, n, self.strategy, self.newest_first)
+    }
+
+    /// Sorts all items and yields them as consecutive pages of `n` items,
+    /// the last one possibly shorter, e.g. for paged responses. Unlike
+    /// [`sort_within_chunks`](SortBy::sort_within_chunks), the pages
+    /// together are in the global order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn sorted_chunks(mut self, n: usize) -> SortedPages<I::Item> {
+        assert!(n != 0, "chunk size must be non-zero");
+        SortedPages::new(self.take_vec(), n)
+    }
+
+    #[cfg(not(feature = "stats"))]
+    fn comparator(&self) -> &C {
+        &self.compare
+    }
+
+    #[cfg(feature = "stats")]
+    fn comparator(&self) -> stats::Counted<'_, C> {
+        stats::Counted {
+            compare: &self.compare,
+            stats: &self.stats,
+        }
+    }
+
+    /// Collects the source iterator, if it was not collected yet, without
+    /// sorting it.
+    fn buffer_source(&mut self) {
+        if let IterState::Unsorted(ref mut iter @ Some(Source::Iter(_))) = self.iter {
+            let iter = iter.take().unwrap();
+            let vec = self.collect_with_capacity(iter);
+            self.iter = IterState::Unsorted(Some(Source::Buffered(vec.into_iter())));
+        }
+    }
+
+    fn take_vec(&mut self) -> Vec<I::Item> {
+        match self.iter {
+            IterState::Unsorted(ref mut iter) => {
+                let iter = iter.take().unwrap();
+                self.collect_sorted(iter)
+            }
+            // the sort already yielded items, so the terminators return the
+            // remainder, see `into_vec`
+            IterState::Sorted(ref mut iter) => {
+                core::mem::replace(iter, Vec::new().into_iter()).collect()
+            }
+        }
+    }
+
+    fn collect_with_capacity<J: Iterator>(&self, iter: J) -> Vec<J::Item> {
+        let mut span = trace::collect::<J::Item>().observed_by(self.observer);
+        let (lower, _) = iter.size_hint();
+        let mut vec = Vec::with_capacity(self.capacity.unwrap_or(0).max(lower));
+        vec.extend(iter);
+        span.record_items(vec.len());
+        vec
+    }
+
+    fn try_collect_with_capacity<J: Iterator>(
+        &self,
+        iter: J,
+    ) -> Result<Vec<J::Item>, TryReserveError> {
+        let mut span = trace::collect::<J::Item>().observed_by(self.observer);
+        let (lower, _) = iter.size_hint();
+        let mut vec = Vec::new();
+        vec.try_reserve(self.capacity.unwrap_or(0).max(lower))?;
+        for item in iter {
+            if vec.len() == vec.capacity() {
+                vec.try_reserve(1)?;
+            }
+            vec.push(item);
+        }
+        span.record_items(vec.len());
+        Ok(vec)
+    }
+
+    fn collect_sorted(&mut self, iter: Source<I>) -> Vec<I::Item> {
+        let mut vec = self.collect_with_capacity(iter);
+        self.sort_items(&mut vec);
+        vec
+    }
+
+    /// Sorts with the configured [`Strategy`], honoring
+    /// [`then_sort_by_input_order_desc`] with either.
+    ///
+    /// [`then_sort_by_input_order_desc`]: SortBy::then_sort_by_input_order_desc
+    fn sort_items(&self, vec: &mut Vec<I::Item>) {
+        let _span = trace::sort(vec).observed_by(self.observer);
+        if cfg!(debug_assertions) && self.check_consistency {
+            consistency::check(vec, |a, b| self.comparator().compare(a, b));
+        }
+        sorter::sort_items(vec, self.strategy, self.newest_first, |a, b| {
+            self.comparator().compare(a, b)
+        });
+    }
+
+    /// Orders tied items by their input positions `a` and `b`.
+    fn tie_break(&self, a: usize, b: usize) -> Ordering {
+        if self.newest_first {
+            b.cmp(&a)
+        } else {
+            a.cmp(&b)
+        }
+    }
+}
+
+impl<'a, I, C> Clone for SortBy<'a, I, C>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        SortBy {
+            iter: self.iter.clone(),
+            compare: self.compare.clone(),
+            capacity: self.capacity,
+            newest_first: self.newest_first,
+            strategy: self.strategy,
+            check_consistency: self.check_consistency,
+            plan: self.plan.clone(),
+            observer: self.observer,
+            #[cfg(feature = "stats")]
+            stats: self.stats.clone(),
+            yielded: self.yielded,
+            lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a, I, C> core::fmt::Debug for SortBy<'a, I, C>
+where
+    I: Iterator,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SortBy")
+            .field("plan", &format_args!("{}", self.plan))
+            .field("sorted", &matches!(self.iter, IterState::Sorted(_)))
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Same as [`SortBy::into_vec`], but fails with
+/// [`SortError::AlreadyStarted`] instead of silently returning the remaining
+/// items of a sort that already yielded some.
+impl<'a, I, C> TryFrom<SortBy<'a, I, C>> for Vec<I::Item>
+where
+    I: Iterator,
+    C: Compare<I::Item>,
+{
+    type Error = SortError;
+
+    fn try_from(mut val: SortBy<'a, I, C>) -> Result<Self, SortError> {
+        if val.has_started() {
+            return Err(SortError::AlreadyStarted);
+        }
+        Ok(val.take_vec())
+    }
+}
+
+impl<'a, I, C> Iterator for SortBy<'a, I, C>
+where
+    I: Iterator,
+    C: Compare<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = match self.iter {
+            IterState::Unsorted(ref mut iter) => {
+                let iter = iter.take().unwrap();
+                let vec = self.collect_sorted(iter);
+                self.iter = IterState::Sorted(vec.into_iter());
+                self.iter.unwrap_sorted().next()
+            }
+            IterState::Sorted(ref mut iter) => iter.next(),
+        };
+        self.yielded |= item.is_some();
+        item
+    }
+}
+
+pub trait SortByIteratorExt: Iterator {
+    fn sort_by<'a, F, V>(self, f: F) -> SortBy<'a, Self, ByKey<F, V>>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        SortBy::new(self, ByKey::new(f), Direction::Ascending)
+    }
+
+    fn sort_by_desc<'a, F, V>(self, f: F) -> SortBy<'a, Self, ByKeyDesc<F, V>>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        SortBy::new(self, ByKeyDesc::new(f), Direction::Descending)
+    }
+
+    /// Sorts by `f` the way `config` says, e.g. one config shared by all
+    /// call sites of a service instead of a choice between method names.
+    /// The items are sorted right away.
+    fn sort_by_with<F, V>(
+        self,
+        f: F,
+        config: &SortConfig<'_>,
+    ) -> Result<alloc::vec::IntoIter<Self::Item>, SortError>
+    where
+        Self::Item: Send,
+        V: PartialOrd + Send,
+        F: Fn(&Self::Item) -> V + Sync,
+        Self: Sized,
+    {
+        config::sort_with(self, f, false, config).map(Vec::into_iter)
+    }
+
+    fn sort_by_desc_with<F, V>(
+        self,
+        f: F,
+        config: &SortConfig<'_>,
+    ) -> Result<alloc::vec::IntoIter<Self::Item>, SortError>
+    where
+        Self::Item: Send,
+        V: PartialOrd + Send,
+        F: Fn(&Self::Item) -> V + Sync,
+        Self: Sized,
+    {
+        config::sort_with(self, f, true, config).map(Vec::into_iter)
+    }
+
+    /// Sorts by the item type's [`DefaultSort`] order.
+    fn sort_default<'a>(self) -> SortBy<'a, Self, FnCompare<DefaultSortFn<Self::Item>>>
+    where
+        Self::Item: DefaultSort,
+        Self: Sized,
+    {
+        let mut sort = SortBy::new(
+            self,
+            FnCompare(DefaultSort::default_sort as DefaultSortFn<Self::Item>),
+            Direction::Ascending,
+        );
+        sort.plan.name_last("default order");
+        sort
+    }
+
+    /// Splits the items into `k` buckets whose key ranges do not overlap,
+    /// ascending, e.g. to sort each bucket independently and concatenate the
+    /// results. The splitters are picked from an evenly spaced sample.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    fn partition_by_quantiles<K, F>(self, k: usize, key: F) -> Vec<Vec<Self::Item>>
+    where
+        K: PartialOrd,
+        F: Fn(&Self::Item) -> K,
+        Self: Sized,
+    {
+        quantiles::partition_by_quantiles(self.collect(), k, key)
+    }
+
+    /// Sorts the items by `key` within the buckets `bucket` assigns them to,
+    /// and concatenates the buckets in ascending bucket order. With roughly
+    /// even buckets this does less work than one global sort. The items are
+    /// sorted right away.
+    fn bucket_sort_by<B, G, V, F>(self, bucket: G, key: F) -> alloc::vec::IntoIter<Self::Item>
+    where
+        B: Ord,
+        G: Fn(&Self::Item) -> B,
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        buckets::bucket_sort_by(self, bucket, key).into_iter()
+    }
+
+    /// Like [`bucket_sort_by`](SortByIteratorExt::bucket_sort_by), but sorts
+    /// the buckets in parallel. Requires the `rayon` feature.
+    ///
+    /// The sort is stable, and the output is the same as the serial
+    /// [`bucket_sort_by`](SortByIteratorExt::bucket_sort_by) for the same
+    /// input, whatever the number of threads: items are distributed in input
+    /// order, each bucket is sorted by one thread, and the buckets are
+    /// concatenated in bucket order.
+    #[cfg(feature = "rayon")]
+    fn par_bucket_sort_by<B, G, V, F>(self, bucket: G, key: F) -> alloc::vec::IntoIter<Self::Item>
+    where
+        Self::Item: Send,
+        B: Ord,
+        G: Fn(&Self::Item) -> B,
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V + Sync,
+        Self: Sized,
+    {
+        self.par_bucket_sort_by_in(bucket, key, Parallelism::Global)
+    }
+
+    /// Like [`par_bucket_sort_by`](SortByIteratorExt::par_bucket_sort_by),
+    /// but sorts the buckets where `parallelism` says, e.g. on a dedicated
+    /// thread pool. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    fn par_bucket_sort_by_in<B, G, V, F>(
+        self,
+        bucket: G,
+        key: F,
+        parallelism: Parallelism<'_>,
+    ) -> alloc::vec::IntoIter<Self::Item>
+    where
+        Self::Item: Send,
+        B: Ord,
+        G: Fn(&Self::Item) -> B,
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V + Sync,
+        Self: Sized,
+    {
+        buckets::par_bucket_sort_by(self, bucket, key, parallelism).into_iter()
+    }
+
+    /// Counts the keys into `k` bins placed as `binning` says, e.g. for a
+    /// quick distribution of response times. The keys are sorted to find the
+    /// boundaries; NaN keys are skipped. Returns no bins for no keys.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let latencies = [12.0, 15.0, 11.0, 90.0, 14.0, 13.0];
+    /// let bins = latencies.iter().histogram_by(|v| **v, 2, Binning::EqualFrequency);
+    /// assert_eq!(bins[0], Bin { start: 11.0, end: 14.0, count: 3 });
+    /// assert_eq!(bins[1], Bin { start: 14.0, end: 90.0, count: 3 });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    fn histogram_by<F>(self, key: F, k: usize, binning: Binning) -> Vec<Bin>
+    where
+        F: Fn(&Self::Item) -> f64,
+        Self: Sized,
+    {
+        histogram::histogram(self.map(|item| key(&item)).collect(), k, binning)
+    }
+
+    /// Estimates the quantiles `qs` of the keys in a single pass without
+    /// buffering the stream, e.g. latency percentiles, using a
+    /// [`QuantileSketch`] with [`DEFAULT_EPSILON`]. Returns `None` for an
+    /// empty iterator. Requires the `sketch` feature.
+    #[cfg(feature = "sketch")]
+    fn approx_quantiles_by<K, F>(self, key: F, qs: &[f64]) -> Option<Vec<K>>
+    where
+        K: PartialOrd + Clone,
+        F: Fn(&Self::Item) -> K,
+        Self: Sized,
+    {
+        let mut sketch = QuantileSketch::new(DEFAULT_EPSILON);
+        self.for_each(|item| sketch.insert(key(&item)));
+        qs.iter().map(|q| sketch.quantile(*q).cloned()).collect()
+    }
+
+    /// Sorts a uniform random sample of `k` items, e.g. to look at
+    /// representative rows of a huge iterator. The sample is drawn in a single
+    /// pass with reservoir sampling, so only `k` items are kept in memory.
+    /// Requires the `rand` feature.
+    #[cfg(feature = "rand")]
+    fn sorted_sample_by<'a, F, V, R>(
+        self,
+        k: usize,
+        f: F,
+        rng: &mut R,
+    ) -> SortBy<'a, alloc::vec::IntoIter<Self::Item>, ByKey<F, V>>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        R: rand::Rng + ?Sized,
+        Self: Sized,
+    {
+        sample::reservoir_sample(self, k, rng)
+            .into_iter()
+            .sort_by(f)
+    }
+
+    /// Yields the items in a random order drawn from `rng`, the inverse of
+    /// sorting. The items are buffered on the first call to `next`. Requires
+    /// the `rand` feature.
+    #[cfg(feature = "rand")]
+    fn shuffle<R>(self, rng: &mut R) -> Shuffle<'_, Self, R>
+    where
+        R: rand::Rng + ?Sized,
+        Self: Sized,
+    {
+        Shuffle::new(self, rng)
+    }
+
+    /// Sorts the items by their weighted [`Score`](normalize::Score),
+    /// lowest first. The items are buffered once to normalize every key over
+    /// all of them, and sorted right away. Equal scores keep the input order.
+    fn sort_by_score(
+        self,
+        score: &normalize::Score<'_, Self::Item>,
+    ) -> alloc::vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+    {
+        normalize::sort_by_score(self.collect(), score, false).into_iter()
+    }
+
+    /// Like [`sort_by_score`](SortByIteratorExt::sort_by_score), but highest
+    /// score first.
+    fn sort_by_score_desc(
+        self,
+        score: &normalize::Score<'_, Self::Item>,
+    ) -> alloc::vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+    {
+        normalize::sort_by_score(self.collect(), score, true).into_iter()
+    }
+
+    /// Moves the items matching `pred` to the front, keeping the relative
+    /// order on both sides, like "errors on top, everything else below". This
+    /// is a stable sort by `!pred(item)` done in a single linear pass.
+    fn stable_partition_by<P>(self, pred: P) -> alloc::vec::IntoIter<Self::Item>
+    where
+        P: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        let (mut matching, rest): (Vec<_>, Vec<_>) = self.partition(pred);
+        matching.extend(rest);
+        matching.into_iter()
+    }
+
+    /// Passes the items through unchanged, but panics as soon as an item's
+    /// key is smaller than its predecessor's, to validate the order an
+    /// upstream system promises.
+    fn assert_sorted_by<F, V>(self, f: F) -> AssertSorted<Self, F, V>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        AssertSorted::new(self, f)
+    }
+
+    /// Like [`assert_sorted_by`](SortByIteratorExt::assert_sorted_by), but
+    /// yields out-of-order items as [`OutOfOrder`] errors instead of
+    /// panicking.
+    fn check_sorted_by<F, V>(self, f: F) -> CheckSorted<Self, F, V>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        CheckSorted::new(self, f)
+    }
+
+    /// Finds the longest run of items already in ascending order of `f`, see
+    /// [`SortBy::longest_run`].
+    fn longest_run_by<F, V>(self, f: F) -> Run
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        self.sort_by(f).longest_run()
+    }
+
+    /// Sorts by a string key using the locale rules of `collator`, see
+    /// [`collate`]. Requires the `icu` feature.
+    #[cfg(feature = "icu")]
+    fn sort_by_collated<'a, F>(
+        self,
+        f: F,
+        collator: &'a collate::Collator,
+    ) -> SortBy<'a, Self, collate::ByCollatedKey<'a, F>>
+    where
+        F: Fn(&Self::Item) -> &str,
+        Self: Sized,
+    {
+        SortBy::new(
+            self,
+            collate::ByCollatedKey::new(f, collator),
+            Direction::Ascending,
+        )
+    }
+
+    /// Sorts strings by capture group `group` of `pattern`, parsed with
+    /// `parse`, e.g. the timestamp of log lines or the number in structured
+    /// file names. Strings without a match for the group come last. Requires
+    /// the `regex` feature.
+    ///
+    /// ```
+    /// use regex::Regex;
+    /// use sortby::*;
+    ///
+    /// let pattern = Regex::new(r"^v(\d+)").unwrap();
+    /// let tags = vec!["v10-rc", "v2", "latest", "v1"];
+    /// let sorted: Vec<_> = tags
+    ///     .into_iter()
+    ///     .sort_by_regex_capture(&pattern, 1, |s| s.parse::<u32>().ok())
+    ///     .collect();
+    /// assert_eq!(sorted, vec!["v1", "v2", "v10-rc", "latest"]);
+    /// ```
+    #[cfg(feature = "regex")]
+    fn sort_by_regex_capture<'a, P, K>(
+        self,
+        pattern: &'a regex::Regex,
+        group: usize,
+        parse: P,
+    ) -> SortBy<'a, Self, ByRegexCapture<'a, P, K>>
+    where
+        Self::Item: AsRef<str>,
+        P: Fn(&str) -> K,
+        K: PartialOrd,
+        Self: Sized,
+    {
+        SortBy::new(
+            self,
+            ByRegexCapture::new(pattern, group, parse),
+            Direction::Ascending,
+        )
+    }
+
+    /// Sorts JSON documents by the value `pointer` selects, ordered by
+    /// `order`, see [`json`]. The documents are buffered first to check
+    /// them against a [`strict`](json::JsonOrder::strict) order. Requires
+    /// the `json` feature.
+    #[cfg(feature = "json")]
+    fn sort_by_json_pointer<'a>(
+        self,
+        pointer: &'a str,
+        order: &'a json::JsonOrder,
+    ) -> Result<SortBy<'a, alloc::vec::IntoIter<Self::Item>, json::ByJsonPointer<'a>>, SortError>
+    where
+        Self::Item: core::borrow::Borrow<serde_json::Value>,
+        Self: Sized,
+    {
+        use core::borrow::Borrow;
+
+        let docs: Vec<_> = self.collect();
+        order.check(docs.iter().map(|doc| json::select(doc.borrow(), pointer)))?;
+        Ok(SortBy::new(
+            docs.into_iter(),
+            json::ByJsonPointer::new(pointer, order),
+            Direction::Ascending,
+        ))
+    }
+
+    /// Like [`sort_by_json_pointer`](SortByIteratorExt::sort_by_json_pointer),
+    /// but by several `(pointer, direction)` levels, most significant first.
+    /// A strict `order` checks each level separately. Requires the `json`
+    /// feature.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use sortby::*;
+    /// use sortby::json::JsonOrder;
+    ///
+    /// let tasks = vec![
+    ///     json!({"title": "b", "priority": 1}),
+    ///     json!({"title": "a", "priority": 1}),
+    ///     json!({"title": "c", "priority": 2}),
+    /// ];
+    /// let levels = [("/priority", Direction::Descending), ("/title", Direction::Ascending)];
+    /// let titles: Vec<_> = tasks
+    ///     .iter()
+    ///     .sort_by_json_pointers(&levels, &JsonOrder::new())
+    ///     .unwrap()
+    ///     .map(|task| task["title"].as_str().unwrap())
+    ///     .collect();
+    /// assert_eq!(titles, vec!["c", "a", "b"]);
+    /// ```
+    #[cfg(feature = "json")]
+    fn sort_by_json_pointers<'a>(
+        self,
+        levels: &'a [(&'a str, Direction)],
+        order: &'a json::JsonOrder,
+    ) -> Result<SortBy<'a, alloc::vec::IntoIter<Self::Item>, json::ByJsonPointers<'a>>, SortError>
+    where
+        Self::Item: core::borrow::Borrow<serde_json::Value>,
+        Self: Sized,
+    {
+        use core::borrow::Borrow;
+
+        let docs: Vec<_> = self.collect();
+        for (pointer, _) in levels {
+            order.check(docs.iter().map(|doc| json::select(doc.borrow(), pointer)))?;
+        }
+        let mut sort = SortBy::new(
+            docs.into_iter(),
+            json::ByJsonPointers::new(levels, order),
+            Direction::Ascending,
+        );
+        sort.plan.levels.clear();
+        for (_, direction) in levels {
+            sort.plan.push(*direction);
+        }
+        Ok(sort)
+    }
+
+    /// Sorts by an enum key in the explicit variant order of `order` rather
+    /// than the declaration order.
+    fn sort_by_enum_order<'a, F, E>(
+        self,
+        f: F,
+        order: &'a EnumOrder<E>,
+    ) -> SortBy<'a, Self, ByEnumOrder<'a, F, E>>
+    where
+        F: Fn(&Self::Item) -> &E,
+        Self: Sized,
+    {
+        SortBy::new(self, ByEnumOrder::new(f, order), Direction::Ascending)
+    }
+
+    /// Sorts exactly `N` items into an array without allocating, for targets
+    /// that cannot use the heap at all. Fails if the iterator yields fewer or
+    /// more than `N` items.
+    ///
+    /// ```
+    /// use sortby::*;
+    ///
+    /// let sorted: [_; 3] = [3, 1, 2].iter().sort_by_into_array(|v| **v).unwrap();
+    /// assert_eq!(sorted, [&1, &2, &3]);
+    /// ```
+    fn sort_by_into_array<F, V, const N: usize>(self, f: F) -> Result<[Self::Item; N], SortError>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        array::sort_into_array(self, &ByKey::new(f), false)
+    }
+
+    fn sort_by_desc_into_array<F, V, const N: usize>(
+        self,
+        f: F,
+    ) -> Result<[Self::Item; N], SortError>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        array::sort_into_array(self, &ByKeyDesc::new(f), false)
+    }
+
+    /// Sorts by `f` while the items matching `is_pinned` keep their
+    /// positions, see [`SortBy::with_pins`].
+    fn sort_by_with_pins<P, F, V>(self, is_pinned: P, f: F) -> alloc::vec::IntoIter<Self::Item>
+    where
+        P: Fn(&Self::Item) -> bool,
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        self.sort_by(f).with_pins(is_pinned, PinMode::KeepPosition)
+    }
+
+    /// Sorts by a key with few distinct values, comparing each distinct key's
+    /// rank instead of the key itself, see [`SortBy::sort_by_interned`].
+    fn sort_by_interned<K, F>(self, f: F) -> alloc::vec::IntoIter<Self::Item>
+    where
+        K: Ord,
+        F: Fn(&Self::Item) -> K,
+        Self: Sized,
+    {
+        intern::sort_interned(self.collect(), f, false).into_iter()
+    }
+
+    /// Sorts by a field chosen at runtime through the items' serde
+    /// serialization, see [`SortBy::sort_by_serde_field`]. Requires the
+    /// `serde` feature.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use sortby::*;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Row {
+    ///     name: &'static str,
+    ///     score: f64,
+    /// }
+    ///
+    /// let rows = vec![Row { name: "a", score: 0.5 }, Row { name: "b", score: 0.25 }];
+    /// let names: Vec<_> = rows
+    ///     .into_iter()
+    ///     .sort_by_serde_field("score")
+    ///     .unwrap()
+    ///     .map(|r| r.name)
+    ///     .collect();
+    /// assert_eq!(names, vec!["b", "a"]);
+    /// ```
+    #[cfg(feature = "serde")]
+    fn sort_by_serde_field(self, field: &str) -> Result<alloc::vec::IntoIter<Self::Item>, SortError>
+    where
+        Self::Item: serde::Serialize,
+        Self: Sized,
+    {
+        fields::sort_by_serde_field(self.collect(), field, false).map(Vec::into_iter)
+    }
+
+    #[cfg(feature = "serde")]
+    fn sort_by_serde_field_desc(
+        self,
+        field: &str,
+    ) -> Result<alloc::vec::IntoIter<Self::Item>, SortError>
+    where
+        Self::Item: serde::Serialize,
+        Self: Sized,
+    {
+        fields::sort_by_serde_field(self.collect(), field, true).map(Vec::into_iter)
+    }
+
+    fn sort_by_interned_desc<K, F>(self, f: F) -> alloc::vec::IntoIter<Self::Item>
+    where
+        K: Ord,
+        F: Fn(&Self::Item) -> K,
+        Self: Sized,
+    {
+        intern::sort_interned(self.collect(), f, true).into_iter()
+    }
+
+    /// Orders whole groups of items by an aggregate over each group while the
+    /// items within a group keep their input order, see
+    /// [`SortBy::sort_groups_by_aggregate`].
+    #[cfg(feature = "std")]
+    fn sort_groups_by_aggregate<K, A, G, F>(
+        self,
+        group_key: G,
+        aggregate: F,
+    ) -> alloc::vec::IntoIter<Self::Item>
+    where
+        K: Eq + core::hash::Hash,
+        A: PartialOrd,
+        G: Fn(&Self::Item) -> K,
+        F: Fn(&[Self::Item]) -> A,
+        Self: Sized,
+    {
+        groups::sort_groups(self.collect(), group_key, aggregate, false).into_iter()
+    }
+
+    #[cfg(feature = "std")]
+    fn sort_groups_by_aggregate_desc<K, A, G, F>(
+        self,
+        group_key: G,
+        aggregate: F,
+    ) -> alloc::vec::IntoIter<Self::Item>
+    where
+        K: Eq + core::hash::Hash,
+        A: PartialOrd,
+        G: Fn(&Self::Item) -> K,
+        F: Fn(&[Self::Item]) -> A,
+        Self: Sized,
+    {
+        groups::sort_groups(self.collect(), group_key, aggregate, true).into_iter()
+    }
+
+    /// Sorts a stream in which every item is at most `k` positions away from
+    /// its sorted position, buffering only `k + 1` items at a time. Items that
+    /// are further off are emitted late.
+    fn reorder_within<F, V>(self, k: usize, f: F) -> ReorderWithin<Self, F, V>
+    where
+        V: PartialOrd,
+        F: Fn(&Self::Item) -> V,
+        Self: Sized,
+    {
+        ReorderWithin::new(self, k, f)
+    }
+
+    /// Orders the items by the event time `f` returns, holding each one back
+    /// until an item at least `lateness` newer has been seen. See
+    /// [`WatermarkSorter`].
+    fn sort_by_watermark<'a, F, K, D>(self, lateness: D, f: F) -> WatermarkSorted<'a, Self, K, D, F>
+    where
+        K: PartialOrd + Clone + core::ops::Add<D, Output = K>,
+        D: Clone,
+        F: Fn(&Self::Item) -> K,
+        Self: Sized,
+    {
+        WatermarkSorted::new(self, lateness, f)
+    }
+}
+
+impl<T: ?Sized> SortByIteratorExt for T where T: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    struct Person {
+        pub age: i32,
+        pub name: &'static str,
+    }
+
+    #[test]
+    fn works_with_empty_iterators() {
+        let input = Vec::<i32>::new();
+        let actual: Vec<_> = input.into_iter().sort_by(|v| *v).collect();
+
+        assert_equal(actual, Vec::<i32>::new());
+    }
+
+    #[test]
+    fn converts_into_vec_to_avoid_double_allocation() {
+        let input = vec![5, 2, 3];
+        let actual = Vec::try_from(input.into_iter().sort_by(|v| *v)).unwrap();
+
+        assert_equal(actual, vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn refuses_to_convert_a_started_sort() {
+        let mut sort = vec![5, 2, 3].into_iter().sort_by(|v| *v);
+        assert_eq!(sort.next(), Some(2));
+
+        assert_eq!(Vec::try_from(sort), Err(SortError::AlreadyStarted));
+    }
+
+    #[test]
+    fn preallocates_with_capacity_hint() {
+        let input = (0..10).filter(|v| v % 2 == 0).rev();
+        let actual = input.sort_by(|v| *v).with_capacity_hint(64).into_vec();
+
+        assert!(actual.capacity() >= 64);
+        assert_equal(actual, vec![0, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn keeps_ties_in_one_quantile_bucket() {
+        let actual: Vec<_> = vec![1, 2, 2, 2, 2, 3, 4, 5]
+            .into_iter()
+            .sort_by(|v| *v)
+            .with_quantile_bucket(4)
+            .map(|(bucket, _)| bucket)
+            .collect();
+
+        assert_eq!(actual, vec![0, 0, 0, 0, 0, 2, 3, 3]);
+        assert_eq!(
+            std::iter::empty::<u8>()
+                .sort_by(|v| *v)
+                .with_quantile_bucket(4)
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn sorts_into_sorted_vec_fallibly() {
+        let input = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        let actual = input.into_iter().sort_by(|v| v.0).try_into_sorted_vec();
+
+        assert_equal(
+            actual.unwrap(),
+            vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')],
+        );
+    }
+
+    #[test]
+    fn reports_failed_reservation() {
+        let actual = (0..10)
+            .sort_by(|v| *v)
+            .with_capacity_hint(usize::MAX)
+            .try_into_sorted_vec();
+
+        assert!(matches!(actual, Err(SortError::AllocationFailed(_))));
+    }
+
+    #[test]
+    fn averages_percentile_rank_over_ties() {
+        let input = vec![(1, 'a'), (3, 'b'), (1, 'c'), (2, 'd'), (1, 'e')];
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by_desc(|v| v.0)
+            .with_percentile_rank()
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                (0.0, (3, 'b')),
+                (0.25, (2, 'd')),
+                (0.75, (1, 'a')),
+                (0.75, (1, 'c')),
+                (0.75, (1, 'e'))
+            ]
+        );
+
+        let single: Vec<_> = vec![7]
+            .into_iter()
+            .sort_by(|v| *v)
+            .with_percentile_rank()
+            .collect();
+        assert_eq!(single, vec![(0.5, 7)]);
+    }
+
+    #[test]
+    fn skips_disabled_levels() {
+        let input = vec![(1, 'b', 2), (0, 'c', 1), (1, 'a', 3)];
+
+        let sort = input
+            .clone()
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_if(false, |v| v.1)
+            .then_sort_by_desc_if(true, |v| v.2);
+        assert_eq!(sort.describe().levels.len(), 2);
+        assert_eq!(
+            sort.collect::<Vec<_>>(),
+            vec![(0, 'c', 1), (1, 'a', 3), (1, 'b', 2)]
+        );
+
+        let field = |name: &str| -> Option<CompareFn<'_, (i32, char, i32)>> {
+            match name {
+                "letter" => Some(Arc::new(|a, b| a.1.cmp(&b.1))),
+                _ => None,
+            }
+        };
+        let spec: SortSpec = "-letter".parse().unwrap();
+        let actual: Vec<_> = input
+            .clone()
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_spec(&spec, field)
+            .unwrap()
+            .collect();
+        assert_eq!(actual, vec![(0, 'c', 1), (1, 'b', 2), (1, 'a', 3)]);
+
+        let actual: Vec<_> = input
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_spec(None, field)
+            .unwrap()
+            .collect();
+        assert_eq!(actual, vec![(0, 'c', 1), (1, 'b', 2), (1, 'a', 3)]);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn sorts_into_smallvec() {
+        let input = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+
+        let actual = input
+            .iter()
+            .copied()
+            .sort_by(|v| v.0)
+            .into_sorted_smallvec::<4>();
+        assert!(!actual.spilled());
+        assert_eq!(&actual[..], &[(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+
+        let actual = input
+            .iter()
+            .copied()
+            .sort_by(|v| v.0)
+            .into_sorted_smallvec::<2>();
+        assert!(actual.spilled());
+        assert_eq!(&actual[..], &[(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn sorts_into_custom_allocator() {
+        use alloc::alloc::{AllocError, Allocator, Global, Layout};
+        use core::cell::Cell;
+        use core::ptr::NonNull;
+
+        #[derive(Default)]
+        struct Counting(Cell<usize>);
+
+        unsafe impl Allocator for &Counting {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + 1);
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let counting = Counting::default();
+        let input = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        let actual = input.into_iter().sort_by(|v| v.0).into_vec_in(&counting);
+
+        assert_eq!(counting.0.get(), 2);
+        assert_equal(actual, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
+
+    #[test]
+    fn moves_across_threads() {
+        let sort = vec![3, 1, 2]
+            .into_iter()
+            .sort_by_desc(|v| *v)
+            .then_sort_by(|v| v % 2);
+        let actual = std::thread::spawn(move || sort.collect::<Vec<_>>())
+            .join()
+            .unwrap();
+
+        assert_equal(actual, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn clones_sort_pipeline() {
+        let snapshot = [(1, 'b'), (0, 'c'), (1, 'a')];
+        let pipeline = snapshot.iter().sort_by_desc(|v| v.0).then_sort_by(|v| v.1);
+
+        let first: Vec<_> = pipeline.clone().collect();
+        let second: Vec<_> = pipeline.collect();
+
+        assert_equal(&first, &second);
+        assert_equal(first, vec![&(1, 'a'), &(1, 'b'), &(0, 'c')]);
+    }
+
+    #[test]
+    fn boxes_differently_composed_sorts() {
+        let data = [(1, 'b'), (0, 'c'), (1, 'a')];
+        let sorts: Vec<SortBy<'_, _>> = vec![
+            data.iter().sort_by(|v| v.0).boxed(),
+            data.iter()
+                .sort_by_desc(|v| v.0)
+                .then_sort_by(|v| v.1)
+                .boxed(),
+        ];
+
+        let actual: Vec<Vec<_>> = sorts.into_iter().map(|sort| sort.collect()).collect();
+        assert_equal(&actual[0], &[&(0, 'c'), &(1, 'b'), &(1, 'a')]);
+        assert_equal(&actual[1], &[&(1, 'a'), &(1, 'b'), &(0, 'c')]);
+    }
+
+    #[test]
+    fn extracts_composed_comparator() {
+        let data = [(1, 'b'), (0, 'c'), (1, 'a')];
+        let sort = data.iter().sort_by_desc(|v| v.0).then_sort_by(|v| v.1);
+
+        {
+            let compare = sort.compare_fn();
+            let mut sorted = data.iter().collect::<Vec<_>>();
+            sorted.sort_by(|a, b| compare(a, b));
+            assert_eq!(sorted.binary_search_by(|v| compare(v, &&(1, 'b'))), Ok(1));
+        }
+
+        let compare = sort.into_compare();
+        assert_eq!(compare(&&(0, 'c'), &&(1, 'a')), Ordering::Greater);
+    }
+
+    #[test]
+    fn maps_existing_comparator() {
+        let reversed = true;
+        let actual: Vec<_> = vec![(1, 'a'), (0, 'b'), (1, 'c')]
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by(|v| v.1)
+            .map_compare(|cmp| {
+                move |a: &(i32, char), b: &(i32, char)| {
+                    let ordering = cmp.compare(a, b);
+                    if reversed {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                }
+            })
+            .collect();
+
+        assert_equal(actual, vec![(1, 'c'), (1, 'a'), (0, 'b')]);
+    }
+
+    #[test]
+    fn breaks_ties_by_reverse_input_order() {
+        let input = vec![(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd'), (1, 'e')];
+        let expected = vec![(0, 'd'), (0, 'b'), (1, 'e'), (1, 'c'), (1, 'a')];
+
+        let sort = input
+            .clone()
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_input_order_desc();
+        assert_equal(sort.clone(), expected.clone());
+        assert_equal(sort.clone().page(1, 2), expected[2..4].to_vec());
+        assert_equal(sort.try_into_sorted_vec().unwrap(), expected);
+    }
+
+    #[test]
+    fn breaks_ties_by_reverse_input_order_in_every_sort() {
+        let input = vec![(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd'), (1, 'e')];
+
+        let unstable = input
+            .clone()
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_input_order_desc()
+            .unstable();
+        assert_equal(
+            unstable,
+            vec![(0, 'd'), (0, 'b'), (1, 'e'), (1, 'c'), (1, 'a')],
+        );
+
+        let chunks = input
+            .clone()
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_input_order_desc()
+            .sort_within_chunks(3);
+        assert_equal(
+            chunks,
+            vec![(0, 'b'), (1, 'c'), (1, 'a'), (0, 'd'), (1, 'e')],
+        );
+
+        let pinned = input
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_input_order_desc()
+            .unstable()
+            .with_pins(|v| v.1 == 'c', PinMode::KeepPosition);
+        assert_equal(
+            pinned,
+            vec![(0, 'd'), (0, 'b'), (1, 'c'), (1, 'e'), (1, 'a')],
+        );
+
+        // large enough to leave the small-slice sorts of std
+        let input: Vec<_> = (0..1000).map(|v| (v * 7919 % 5, v)).collect();
+        let mut expected = input.clone();
+        expected.reverse();
+        expected.sort_by_key(|v| v.0);
+        let unstable = input
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_input_order_desc()
+            .unstable();
+        assert_equal(unstable, expected);
+    }
+
+    #[test]
+    fn keeps_ties_in_input_order() {
+        // large enough to leave the small-slice insertion sort of std
+        let input: Vec<_> = (0..1000).map(|v| (v * 7919 % 5, v)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|v| v.0);
+
+        let sort = input.clone().into_iter().sort_by(|v| v.0);
+        assert!(sort.is_stable());
+        assert_equal(sort, expected.clone());
+
+        let sort = input.into_iter().sort_by(|v| v.0).unstable();
+        assert!(!sort.is_stable());
+        let actual: Vec<_> = sort.collect();
+        assert!(actual.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn peeks_min_before_sorting() {
+        let mut sort = vec![(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]
+            .into_iter()
+            .sort_by(|v| v.0);
+
+        assert_eq!(sort.peek_min(), Some(&(1, 'b')));
+        assert_eq!(sort.peek_min(), Some(&(1, 'b')));
+        assert_equal(sort, vec![(1, 'b'), (1, 'd'), (2, 'c'), (3, 'a')]);
+
+        let mut empty = Vec::<i32>::new().into_iter().sort_by(|v| *v);
+        assert_eq!(empty.peek_min(), None);
+    }
+
+    #[test]
+    fn starts_only_when_an_item_was_yielded() {
+        let mut sort = vec![3, 1, 2].into_iter().sort_by(|v| *v);
+        sort.peek_min();
+        sort.longest_run();
+        assert!(!sort.has_started());
+
+        assert_eq!(sort.next(), Some(1));
+        let sort = sort.then_sort_by(|v| -v);
+        assert!(sort.has_started());
+        assert!(sort.clone().boxed().has_started());
+
+        let mut empty = Vec::<i32>::new().into_iter().sort_by(|v| *v);
+        assert_eq!(empty.next(), None);
+        assert!(!empty.has_started());
+    }
+
+    #[test]
+    fn sorts_references_into_owned_items() {
+        let names = [String::from("b"), String::from("c"), String::from("a")];
+        let sorted: Vec<String> = names
+            .iter()
+            .sort_by(|v| v.as_str())
+            .sorted_cloned()
+            .collect();
+        assert_eq!(sorted, vec!["a", "b", "c"]);
+
+        let input = [3, 1, 2];
+        assert_equal(input.iter().sort_by(|v| **v).sorted_copied(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn sorts_by_default_order() {
+        struct Version(u32, u32);
+
+        impl DefaultSort for Version {
+            fn default_sort(a: &Self, b: &Self) -> Ordering {
+                (a.0, a.1).cmp(&(b.0, b.1))
+            }
+        }
+
+        let input = vec![Version(1, 2), Version(0, 9), Version(1, 0)];
+        let sort = input.into_iter().sort_default();
+        assert_eq!(sort.describe().to_string(), "by default order ascending");
+        assert_equal(sort.map(|v| (v.0, v.1)), vec![(0, 9), (1, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn partitions_stably() {
+        let input = vec![(1, "ok"), (2, "err"), (3, "ok"), (4, "err"), (5, "ok")];
+
+        let actual = input.into_iter().stable_partition_by(|v| v.1 == "err");
+
+        assert_equal(actual.map(|v| v.0), vec![2, 4, 1, 3, 5]);
+    }
+
+    #[test]
+    fn recovers_unconsumed_iterator() {
+        let sort = vec![3, 1, 2].into_iter().sort_by(|v| *v);
+        let (iter, compare) = sort.into_inner().ok().unwrap();
+        assert_equal(iter, vec![3, 1, 2]);
+        assert_eq!(compare.compare(&1, &2), Ordering::Less);
+
+        let mut sort = vec![3, 1, 2].into_iter().sort_by(|v| *v);
+        assert_eq!(sort.next(), Some(1));
+        assert_equal(sort.into_inner().err().unwrap(), vec![2, 3]);
+    }
+
+    #[test]
+    fn returns_single_page() {
+        let input: Vec<_> = (0..100).map(|v| (v * 37 % 100, v % 3)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|v| v.1);
+
+        for page in 0..4 {
+            let actual = input.clone().into_iter().sort_by(|v| v.1).page(page, 30);
+            let start = page * 30;
+            let end = (start + 30).min(100);
+            assert_equal(actual, expected[start..end].to_vec());
+        }
+
+        let past_end = input.into_iter().sort_by(|v| v.1).page(4, 30);
+        assert!(past_end.is_empty());
+    }
+
+    #[test]
+    fn resumes_after_boundary_key() {
+        let input = vec![5, 3, 8, 1, 3];
+
+        let actual = input.into_iter().sort_by(|v| *v).skip_after_key(&3, |v| *v);
+
+        assert_equal(actual, vec![5, 8]);
+    }
+
+    #[test]
+    fn takes_until_key_bound() {
+        let scores = [0.5, 0.9, 0.8, 0.95, 0.1];
+
+        let best: Vec<_> = scores
+            .iter()
+            .sort_by_desc(|v| **v)
+            .take_until_key(Bound::Included(0.8), |v| **v)
+            .collect();
+        assert_equal(best, vec![&0.95, &0.9, &0.8]);
+
+        let worst: Vec<_> = scores
+            .iter()
+            .sort_by(|v| **v)
+            .take_until_key(Bound::Excluded(0.8), |v| **v)
+            .collect();
+        assert_equal(worst, vec![&0.1, &0.5]);
     }
 
     #[test]
     fn sorts_floats() {
-        let input = vec![5.0, 1.0, 2.0];
-        let actual: Vec<_> = input.iter().sort_by(|v| *v).map(|v| *v).collect();
+        let input = [5.0, 1.0, 2.0];
+        let actual: Vec<_> = input.iter().sort_by(|v| *v).copied().collect();
 
         assert_equal(actual, vec![1.0, 2.0, 5.0]);
     }
@@ -251,7 +2679,7 @@ mod tests {
                 (None, None) => return,
                 (a, b) => {
                     let equal = match (&a, &b) {
-                        (&Some(ref a), &Some(ref b)) => a == b,
+                        (Some(a), Some(b)) => a == b,
                         _ => false,
                     };
                     assert!(
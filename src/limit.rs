@@ -0,0 +1,48 @@
+/// What [`SortBy::max_items`](crate::SortBy::max_items) does when the source
+/// yields more items than allowed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Overflow {
+    /// Fail with [`SortError::LimitExceeded`](crate::SortError::LimitExceeded).
+    #[default]
+    Error,
+    /// Keep the first items the source yielded, up to the limit, and sort
+    /// only those.
+    Truncate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SortByIteratorExt, SortError};
+
+    #[test]
+    fn stops_reading_unbounded_input() {
+        let actual = (0..).sort_by(|v| *v).max_items(100, Overflow::Error);
+
+        assert_eq!(actual.err(), Some(SortError::LimitExceeded { limit: 100 }));
+    }
+
+    #[test]
+    fn truncates_to_first_items() {
+        let actual: Vec<_> = vec![5, 1, 4, 2, 3]
+            .into_iter()
+            .sort_by(|v| *v)
+            .max_items(3, Overflow::Truncate)
+            .unwrap()
+            .collect();
+
+        assert_eq!(actual, vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn accepts_input_within_limit() {
+        let actual: Vec<_> = vec![3, 1, 2]
+            .into_iter()
+            .sort_by_desc(|v| *v)
+            .max_items(3, Overflow::Error)
+            .unwrap()
+            .collect();
+
+        assert_eq!(actual, vec![3, 2, 1]);
+    }
+}
@@ -0,0 +1,202 @@
+#[cfg(feature = "std")]
+use std::collections::hash_map::{self, HashMap};
+
+use alloc::collections::btree_map::{self, BTreeMap};
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use core::cmp::Ordering;
+
+use crate::{compare_by, compare_by_desc, CompareFn, Direction, SortBy};
+
+fn by_value<'a, I, K, V, F, W>(entries: I, f: F, direction: Direction) -> SortBy<'a, I>
+where
+    I: Iterator<Item = (K, V)>,
+    W: PartialOrd,
+    F: Fn(&V) -> W + Send + Sync + 'a,
+{
+    let value = move |entry: &(K, V)| f(&entry.1);
+    let compare: CompareFn<'a, (K, V)> = match direction {
+        Direction::Ascending => compare_by(value),
+        Direction::Descending => compare_by_desc(value),
+    };
+    let mut sort = SortBy::new(entries, compare, direction);
+    sort.plan.name_last("value");
+    sort
+}
+
+/// Re-sorts the entries of an owned `BTreeMap` by their values.
+///
+/// The sort is stable and the map yields its entries in key order, so
+/// entries with equal values stay in ascending key order unless further
+/// levels are added.
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use sortby::*;
+///
+/// let counts: BTreeMap<_, _> = vec![("b", 3), ("a", 3), ("c", 7)].into_iter().collect();
+/// let top: Vec<_> = counts.sorted_by_value_desc(|count| *count).collect();
+/// assert_eq!(top, vec![("c", 7), ("a", 3), ("b", 3)]);
+/// ```
+pub trait BTreeMapSortExt<K, V> {
+    fn sorted_by_value<'a, F, W>(self, f: F) -> SortBy<'a, btree_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a;
+
+    fn sorted_by_value_desc<'a, F, W>(self, f: F) -> SortBy<'a, btree_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a;
+}
+
+impl<K, V> BTreeMapSortExt<K, V> for BTreeMap<K, V> {
+    fn sorted_by_value<'a, F, W>(self, f: F) -> SortBy<'a, btree_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a,
+    {
+        by_value(self.into_iter(), f, Direction::Ascending)
+    }
+
+    fn sorted_by_value_desc<'a, F, W>(self, f: F) -> SortBy<'a, btree_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a,
+    {
+        by_value(self.into_iter(), f, Direction::Descending)
+    }
+}
+
+/// Sorting for the entries of an owned `HashMap`. Requires the `std`
+/// feature.
+///
+/// The returned [`SortBy`] yields `(K, V)` pairs and can be refined with
+/// further levels like any other sort, e.g. to break ties on the key.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use sortby::*;
+///
+/// let counts: HashMap<_, _> = vec![("b", 3), ("a", 3), ("c", 7)].into_iter().collect();
+/// let top: Vec<_> = counts
+///     .sorted_by_value_desc(|count| *count)
+///     .then_sort_by(|(word, _)| *word)
+///     .collect();
+/// assert_eq!(top, vec![("c", 7), ("a", 3), ("b", 3)]);
+/// ```
+#[cfg(feature = "std")]
+pub trait HashMapSortExt<K, V> {
+    fn sorted_by_value<'a, F, W>(self, f: F) -> SortBy<'a, hash_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a;
+
+    fn sorted_by_value_desc<'a, F, W>(self, f: F) -> SortBy<'a, hash_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a;
+
+    /// Sorts by a key derived from the map key, then by one derived from the
+    /// value for entries whose derived keys are equal.
+    fn sorted_by_key_then_value<'a, FK, U, FV, W>(
+        self,
+        key: FK,
+        value: FV,
+    ) -> SortBy<'a, hash_map::IntoIter<K, V>>
+    where
+        U: PartialOrd,
+        FK: Fn(&K) -> U + Send + Sync + 'a,
+        W: PartialOrd,
+        FV: Fn(&V) -> W + Send + Sync + 'a;
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> HashMapSortExt<K, V> for HashMap<K, V, S> {
+    fn sorted_by_value<'a, F, W>(self, f: F) -> SortBy<'a, hash_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a,
+    {
+        by_value(self.into_iter(), f, Direction::Ascending)
+    }
+
+    fn sorted_by_value_desc<'a, F, W>(self, f: F) -> SortBy<'a, hash_map::IntoIter<K, V>>
+    where
+        W: PartialOrd,
+        F: Fn(&V) -> W + Send + Sync + 'a,
+    {
+        by_value(self.into_iter(), f, Direction::Descending)
+    }
+
+    fn sorted_by_key_then_value<'a, FK, U, FV, W>(
+        self,
+        key: FK,
+        value: FV,
+    ) -> SortBy<'a, hash_map::IntoIter<K, V>>
+    where
+        U: PartialOrd,
+        FK: Fn(&K) -> U + Send + Sync + 'a,
+        W: PartialOrd,
+        FV: Fn(&V) -> W + Send + Sync + 'a,
+    {
+        let compare: CompareFn<'a, (K, V)> = Arc::new(move |a, b| {
+            key(&a.0)
+                .partial_cmp(&key(&b.0))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| {
+                    value(&a.1)
+                        .partial_cmp(&value(&b.1))
+                        .unwrap_or(Ordering::Equal)
+                })
+        });
+        let mut sort = SortBy::new(self.into_iter(), compare, Direction::Ascending);
+        sort.plan.name_last("key");
+        sort.plan.push(Direction::Ascending);
+        sort.plan.name_last("value");
+        sort
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn sorts_entries_by_value() {
+        let map: HashMap<_, _> = vec![('a', 2), ('b', 1), ('c', 3)].into_iter().collect();
+
+        let actual: Vec<_> = map.clone().sorted_by_value(|v| *v).collect();
+        assert_eq!(actual, vec![('b', 1), ('a', 2), ('c', 3)]);
+
+        let actual: Vec<_> = map.sorted_by_value_desc(|v| *v).collect();
+        assert_eq!(actual, vec![('c', 3), ('a', 2), ('b', 1)]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn breaks_key_ties_by_value() {
+        let map: HashMap<_, _> = vec![("B", 1), ("a", 5), ("b", 2), ("A", 9)]
+            .into_iter()
+            .collect();
+
+        let actual: Vec<_> = map
+            .sorted_by_key_then_value(|k| k.to_lowercase(), |v| *v)
+            .collect();
+
+        assert_eq!(actual, vec![("a", 5), ("A", 9), ("B", 1), ("b", 2)]);
+    }
+
+    #[test]
+    fn keeps_key_order_among_equal_values() {
+        let map: BTreeMap<_, _> = vec![("d", 1), ("c", 2), ("b", 1), ("a", 2)]
+            .into_iter()
+            .collect();
+
+        let actual: Vec<_> = map.sorted_by_value_desc(|v| *v).collect();
+
+        assert_eq!(actual, vec![("a", 2), ("c", 2), ("b", 1), ("d", 1)]);
+    }
+}
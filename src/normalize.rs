@@ -0,0 +1,177 @@
+//! Normalizes keys with different units onto a common scale, so they can be
+//! combined into one weighted [`Score`].
+//!
+//! ```
+//! use sortby::*;
+//! use sortby::normalize::{self, Score};
+//!
+//! // (name, price, rating)
+//! let hotels = vec![("a", 120.0, 4.1), ("b", 80.0, 3.2), ("c", 200.0, 4.9)];
+//! let score = Score::new()
+//!     .term(-1.0, normalize::min_max(|h: &(&str, f64, f64)| h.1))
+//!     .term(2.0, normalize::z_score(|h: &(&str, f64, f64)| h.2));
+//!
+//! let best: Vec<_> = hotels.into_iter().sort_by_score_desc(&score).map(|h| h.0).collect();
+//! assert_eq!(best, vec!["c", "a", "b"]);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::trace;
+
+enum Method {
+    MinMax,
+    ZScore,
+}
+
+/// A key together with the way its values are normalized across all items.
+pub struct Normalize<'a, T> {
+    key: Box<dyn Fn(&T) -> f64 + 'a>,
+    method: Method,
+}
+
+/// Rescales the key to `0.0..=1.0`, from the smallest value to the largest.
+/// If all values are equal, they all normalize to `0.0`.
+pub fn min_max<'a, T, F>(key: F) -> Normalize<'a, T>
+where
+    F: Fn(&T) -> f64 + 'a,
+{
+    Normalize {
+        key: Box::new(key),
+        method: Method::MinMax,
+    }
+}
+
+/// Rescales the key to the number of standard deviations from the mean. If
+/// all values are equal, they all normalize to `0.0`.
+pub fn z_score<'a, T, F>(key: F) -> Normalize<'a, T>
+where
+    F: Fn(&T) -> f64 + 'a,
+{
+    Normalize {
+        key: Box::new(key),
+        method: Method::ZScore,
+    }
+}
+
+impl<'a, T> Normalize<'a, T> {
+    /// Returns the normalized key of each item, in the order of `items`.
+    pub fn values(&self, items: &[T]) -> Vec<f64> {
+        let mut values: Vec<f64> = items.iter().map(|item| (self.key)(item)).collect();
+        if values.is_empty() {
+            return values;
+        }
+        let (offset, scale) = match self.method {
+            Method::MinMax => {
+                let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+                let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                (min, max - min)
+            }
+            Method::ZScore => {
+                let len = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / len;
+                let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / len;
+                (mean, sqrt(variance))
+            }
+        };
+        for value in &mut values {
+            *value = if scale > 0.0 {
+                (*value - offset) / scale
+            } else {
+                0.0
+            };
+        }
+        values
+    }
+}
+
+/// A weighted sum of normalized keys, see
+/// [`SortByIteratorExt::sort_by_score`](crate::SortByIteratorExt::sort_by_score).
+pub struct Score<'a, T> {
+    terms: Vec<(f64, Normalize<'a, T>)>,
+}
+
+impl<'a, T> Score<'a, T> {
+    pub fn new() -> Score<'a, T> {
+        Score { terms: Vec::new() }
+    }
+
+    /// Adds a normalized key with `weight`. A negative weight rewards small
+    /// values, e.g. for prices.
+    pub fn term(mut self, weight: f64, key: Normalize<'a, T>) -> Score<'a, T> {
+        self.terms.push((weight, key));
+        self
+    }
+
+    /// Returns the score of each item, in the order of `items`. Each key is
+    /// normalized over all of `items`.
+    pub fn scores(&self, items: &[T]) -> Vec<f64> {
+        let mut scores = alloc::vec![0.0; items.len()];
+        for (weight, key) in &self.terms {
+            for (score, value) in scores.iter_mut().zip(key.values(items)) {
+                *score += weight * value;
+            }
+        }
+        scores
+    }
+}
+
+impl<'a, T> Default for Score<'a, T> {
+    fn default() -> Self {
+        Score::new()
+    }
+}
+
+pub(crate) fn sort_by_score<T>(items: Vec<T>, score: &Score<'_, T>, descending: bool) -> Vec<T> {
+    let scores = score.scores(&items);
+    let mut scored: Vec<_> = scores.into_iter().zip(items).collect();
+    let _span = trace::sort(&scored);
+    scored.sort_by(|a, b| {
+        let ordering = if descending {
+            b.0.partial_cmp(&a.0)
+        } else {
+            a.0.partial_cmp(&b.0)
+        };
+        ordering.unwrap_or(Ordering::Equal)
+    });
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+/// `f64::sqrt` needs std, so this uses Newton's method, starting above the
+/// root and stopping once the estimate no longer decreases.
+fn sqrt(x: f64) -> f64 {
+    if x <= 0.0 || x.is_nan() || x.is_infinite() {
+        return x.max(0.0);
+    }
+    let mut root = x.max(1.0);
+    loop {
+        let next = 0.5 * (root + x / root);
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_keys() {
+        let items = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+        assert_eq!(
+            min_max(|v: &f64| *v).values(&items[..3]),
+            vec![0.0, 1.0, 1.0]
+        );
+        assert_eq!(
+            z_score(|v: &f64| *v).values(&items),
+            vec![-1.5, -0.5, -0.5, -0.5, 0.0, 0.0, 1.0, 2.0]
+        );
+        assert_eq!(min_max(|v: &f64| *v).values(&[3.0, 3.0]), vec![0.0, 0.0]);
+        assert!((sqrt(2.0) - 2f64.sqrt()).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,233 @@
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+
+/// The digit grouping and decimal separators of pre-formatted numbers, e.g.
+/// `1,000.5` in English or `1.000,5` in German, so they can be sorted by
+/// value without parsing them in every caller.
+///
+/// ```
+/// use sortby::*;
+///
+/// let amounts = vec!["1.000,5", "999", "-2,25", "1.000"];
+/// let sorted: Vec<_> = amounts
+///     .into_iter()
+///     .sort_by(|v| NumberFormat::DE.key(v))
+///     .collect();
+/// assert_eq!(sorted, vec!["-2,25", "999", "1.000", "1.000,5"]);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NumberFormat {
+    pub group: char,
+    pub decimal: char,
+}
+
+impl NumberFormat {
+    /// `1,000.5`
+    pub const EN: NumberFormat = NumberFormat::new(',', '.');
+    /// `1.000,5`
+    pub const DE: NumberFormat = NumberFormat::new('.', ',');
+    /// `1'000.5`
+    pub const CH: NumberFormat = NumberFormat::new('\'', '.');
+
+    pub const fn new(group: char, decimal: char) -> NumberFormat {
+        NumberFormat { group, decimal }
+    }
+
+    /// Returns a sort key for the number `s`. Numbers are compared exactly
+    /// by value, regardless of their length, and come before strings that
+    /// are not numbers in this format, which are compared as text.
+    ///
+    /// Surrounding whitespace and a leading `+` or `-` are allowed. Group
+    /// separators must sit between digits of the integer part, but group
+    /// sizes are not checked, so `1,00,000` is read as `100000`.
+    pub fn key(&self, s: &str) -> NumericKey {
+        match self.parse(s.trim()) {
+            Some(key) => key,
+            None => NumericKey::Text(s.to_string()),
+        }
+    }
+
+    fn parse(&self, s: &str) -> Option<NumericKey> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (integer, fraction) = match s.find(self.decimal) {
+            Some(i) => (&s[..i], &s[i + self.decimal.len_utf8()..]),
+            None => (s, ""),
+        };
+        if integer.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let mut digits = String::with_capacity(integer.len());
+        let mut previous_is_digit = false;
+        for c in integer.chars() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                previous_is_digit = true;
+            } else if c == self.group && previous_is_digit {
+                previous_is_digit = false;
+            } else {
+                return None;
+            }
+        }
+        if !previous_is_digit {
+            return None;
+        }
+
+        let integer = digits.trim_start_matches('0').to_string();
+        let fraction = fraction.trim_end_matches('0').to_string();
+        let negative = negative && !(integer.is_empty() && fraction.is_empty());
+        Some(NumericKey::Number {
+            negative,
+            integer,
+            fraction,
+        })
+    }
+}
+
+/// A sort key returned by [`NumberFormat::key`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NumericKey {
+    /// A number, with the integer digits stripped of leading zeros and the
+    /// fraction digits stripped of trailing zeros.
+    Number {
+        negative: bool,
+        integer: String,
+        fraction: String,
+    },
+    /// A string that is not a number in the format.
+    Text(String),
+}
+
+impl NumericKey {
+    fn digits(&self) -> Option<Digits<'_>> {
+        match self {
+            NumericKey::Number {
+                negative,
+                integer,
+                fraction,
+            } => Some(Digits {
+                negative: *negative,
+                integer,
+                fraction,
+            }),
+            NumericKey::Text(_) => None,
+        }
+    }
+}
+
+impl Ord for NumericKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.digits(), other.digits()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => match (self, other) {
+                (NumericKey::Text(a), NumericKey::Text(b)) => a.cmp(b),
+                _ => unreachable!(),
+            },
+        }
+    }
+}
+
+impl PartialOrd for NumericKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A decimal number as borrowed digits, with the integer digits stripped of
+/// leading zeros and the fraction digits stripped of trailing zeros, so
+/// numbers of any length compare by value without being parsed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Digits<'s> {
+    pub(crate) negative: bool,
+    pub(crate) integer: &'s str,
+    pub(crate) fraction: &'s str,
+}
+
+impl<'s> Digits<'s> {
+    /// Reads plain numbers like `-0012.50` without group separators.
+    pub(crate) fn parse(s: &'s str) -> Option<Digits<'s>> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+            return None;
+        }
+        let integer = integer.trim_start_matches('0');
+        let fraction = fraction.trim_end_matches('0');
+        Some(Digits {
+            negative: negative && !(integer.is_empty() && fraction.is_empty()),
+            integer,
+            fraction,
+        })
+    }
+}
+
+impl<'s> Ord for Digits<'s> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => {}
+        }
+        let magnitude = self
+            .integer
+            .len()
+            .cmp(&other.integer.len())
+            .then_with(|| self.integer.cmp(other.integer))
+            .then_with(|| self.fraction.cmp(other.fraction));
+        if self.negative {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl<'s> PartialOrd for Digits<'s> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_by_value() {
+        let en = NumberFormat::EN;
+        assert!(en.key("1,000") > en.key("999"));
+        assert!(en.key("123,456,789,012,345,678,901") > en.key("123,456,789,012,345,678,900.5"));
+        assert!(en.key("-1,000") < en.key("-999.99"));
+        assert!(en.key("0.05") < en.key("0.5"));
+        assert_eq!(en.key(" 007.50 "), en.key("+7.5"));
+        assert_eq!(en.key("-0.0"), en.key("0"));
+
+        let de = NumberFormat::DE;
+        assert!(de.key("1.000,5") > de.key("1.000"));
+        assert!(de.key("1.000,5") < de.key("1.000,75"));
+    }
+
+    #[test]
+    fn puts_malformed_numbers_last() {
+        let en = NumberFormat::EN;
+        for malformed in ["", "n/a", "1,,000", ",100", "100,", "1.2.3", "1.5e3", "-"] {
+            assert_eq!(
+                en.key(malformed),
+                NumericKey::Text(malformed.to_string()),
+                "{}",
+                malformed
+            );
+            assert!(en.key("-1,000,000") < en.key(malformed));
+        }
+        assert_eq!(en.key("1,00,000"), en.key("100000"));
+    }
+}
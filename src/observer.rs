@@ -0,0 +1,148 @@
+/// Hooks into the phases of a sort, e.g. for logging, quota accounting or
+/// alerts on slow sorts, without tying the crate to a telemetry library.
+/// Every method does nothing by default.
+///
+/// An observer is attached to a single sort with [`SortBy::observe`], or to
+/// every sort in the process with [`install_global_observer`]. The global
+/// observer also sees the sorts that do not go through [`SortBy`], like
+/// [`merge_shards`](crate::merge_shards).
+///
+/// [`SortBy`]: crate::SortBy
+/// [`SortBy::observe`]: crate::SortBy::observe
+pub trait SortObserver {
+    /// The source iterator is about to be collected.
+    fn on_collect_start(&self) {}
+
+    /// The source iterator yielded `items` items.
+    fn on_collect_end(&self, _items: usize) {}
+
+    /// `items` items are about to be sorted.
+    fn on_sort_start(&self, _items: usize) {}
+
+    /// `items` items were sorted.
+    fn on_sort_end(&self, _items: usize) {}
+
+    /// `runs` sorted runs were merged into `items` items.
+    fn on_merge(&self, _runs: usize, _items: usize) {}
+}
+
+#[cfg(feature = "std")]
+pub use global::{install_global_observer, remove_global_observer};
+
+#[cfg(feature = "std")]
+mod global {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::RwLock;
+
+    use super::SortObserver;
+
+    type Global = Arc<dyn SortObserver + Send + Sync>;
+
+    static OBSERVER: RwLock<Option<Global>> = RwLock::new(None);
+    /// Set while an observer is installed, so sorts skip the lock otherwise.
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+    /// Attaches `observer` to every sort in the process, replacing the one
+    /// installed before. Requires the `std` feature.
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// use sortby::*;
+    ///
+    /// #[derive(Default)]
+    /// struct SortedItems(AtomicUsize);
+    ///
+    /// impl SortObserver for SortedItems {
+    ///     fn on_sort_end(&self, items: usize) {
+    ///         self.0.fetch_add(items, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// let quota = Arc::new(SortedItems::default());
+    /// install_global_observer(quota.clone());
+    ///
+    /// let _: Vec<_> = vec![3, 1, 2].into_iter().sort_by(|v| *v).collect();
+    /// assert_eq!(quota.0.load(Ordering::Relaxed), 3);
+    /// remove_global_observer();
+    /// ```
+    pub fn install_global_observer(observer: Arc<dyn SortObserver + Send + Sync>) {
+        *OBSERVER.write().unwrap_or_else(|e| e.into_inner()) = Some(observer);
+        INSTALLED.store(true, Ordering::Release);
+    }
+
+    /// Detaches the observer installed with [`install_global_observer`].
+    /// Requires the `std` feature.
+    pub fn remove_global_observer() {
+        INSTALLED.store(false, Ordering::Release);
+        *OBSERVER.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Calls `f` with the global observer, if one is installed.
+    pub(crate) fn notify(f: impl FnOnce(&dyn SortObserver)) {
+        if INSTALLED.load(Ordering::Acquire) {
+            let observer = OBSERVER.read().unwrap_or_else(|e| e.into_inner()).clone();
+            if let Some(observer) = observer {
+                f(&*observer);
+            }
+        }
+    }
+}
+
+/// Calls `f` with the global observer, if one is installed.
+pub(crate) fn notify_global(f: impl FnOnce(&dyn SortObserver)) {
+    #[cfg(feature = "std")]
+    global::notify(f);
+    #[cfg(not(feature = "std"))]
+    let _ = f;
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use std::sync::Mutex;
+
+    use super::SortObserver;
+    use crate::SortByIteratorExt;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<String>>);
+
+    impl SortObserver for Log {
+        fn on_collect_start(&self) {
+            self.0.lock().unwrap().push("collect".to_string());
+        }
+
+        fn on_collect_end(&self, items: usize) {
+            self.0.lock().unwrap().push(format!("collected {}", items));
+        }
+
+        fn on_sort_start(&self, items: usize) {
+            self.0.lock().unwrap().push(format!("sort {}", items));
+        }
+
+        fn on_sort_end(&self, items: usize) {
+            self.0.lock().unwrap().push(format!("sorted {}", items));
+        }
+    }
+
+    #[test]
+    fn reports_phases_of_one_sort() {
+        let log = Log::default();
+
+        let sorted: Vec<_> = vec![3, 1, 2]
+            .into_iter()
+            .sort_by(|v| *v)
+            .observe(&log)
+            .collect();
+
+        assert_eq!(sorted, vec![1, 2, 3]);
+        assert_eq!(
+            *log.0.lock().unwrap(),
+            ["collect", "collected 3", "sort 3", "sorted 3"]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{sorted_vec, ByKey, ByKeyDesc, Compare, CompareFn, Then};
+
+/// A buffer that accepts items one at a time and can hand out its items in
+/// sorted order at any moment, e.g. for a dashboard fed by a stream.
+///
+/// `push` only appends to a pending buffer. The next snapshot sorts just the
+/// pending items and merges them into the already sorted ones in linear time,
+/// so a snapshot after `p` new items costs `O(n + p log p)` instead of a full
+/// re-sort. Equal items keep their arrival order.
+///
+/// ```
+/// use sortby::OnlineSorter;
+///
+/// let mut sorter = OnlineSorter::sort_by(|v: &u32| *v);
+/// sorter.push(3);
+/// sorter.push(1);
+/// assert_eq!(sorter.sorted(), &[1, 3]);
+/// sorter.push(2);
+/// assert_eq!(sorter.sorted(), &[1, 2, 3]);
+/// ```
+///
+/// Like [`SortBy`](crate::SortBy), the comparator `C` is the concrete chain
+/// of sort levels; [`boxed`](OnlineSorter::boxed) erases it into a
+/// [`CompareFn`] when the type has to be named or stored.
+pub struct OnlineSorter<'a, T, C = CompareFn<'a, T>> {
+    compare: C,
+    sorted: Vec<T>,
+    pending: Vec<T>,
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a, T> OnlineSorter<'a, T> {
+    pub fn sort_by<F, V>(f: F) -> OnlineSorter<'a, T, ByKey<F, V>>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V,
+    {
+        OnlineSorter::new(ByKey::new(f))
+    }
+
+    pub fn sort_by_desc<F, V>(f: F) -> OnlineSorter<'a, T, ByKeyDesc<F, V>>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V,
+    {
+        OnlineSorter::new(ByKeyDesc::new(f))
+    }
+}
+
+impl<'a, T, C: Compare<T>> OnlineSorter<'a, T, C> {
+    fn new(compare: C) -> OnlineSorter<'a, T, C> {
+        OnlineSorter {
+            compare,
+            sorted: Vec::new(),
+            pending: Vec::new(),
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Adds a sort level. Items pushed so far are re-sorted by the new chain
+    /// on the next snapshot.
+    pub fn then_sort_by<G, U>(self, f: G) -> OnlineSorter<'a, T, Then<C, ByKey<G, U>>>
+    where
+        U: PartialOrd,
+        G: Fn(&T) -> U,
+    {
+        self.then_with(ByKey::new(f))
+    }
+
+    pub fn then_sort_by_desc<G, U>(self, f: G) -> OnlineSorter<'a, T, Then<C, ByKeyDesc<G, U>>>
+    where
+        U: PartialOrd,
+        G: Fn(&T) -> U,
+    {
+        self.then_with(ByKeyDesc::new(f))
+    }
+
+    fn then_with<D: Compare<T>>(mut self, next: D) -> OnlineSorter<'a, T, Then<C, D>> {
+        self.reset_order();
+        OnlineSorter {
+            compare: Then::new(self.compare, next),
+            sorted: self.sorted,
+            pending: self.pending,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Erases the comparator chain into a boxed [`CompareFn`].
+    pub fn boxed(self) -> OnlineSorter<'a, T>
+    where
+        C: Send + Sync + 'a,
+    {
+        OnlineSorter {
+            compare: self.compare.into_compare_fn(),
+            sorted: self.sorted,
+            pending: self.pending,
+            lifetime: PhantomData,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.pending.push(item);
+    }
+
+    pub fn len(&self) -> usize {
+        self.sorted.len() + self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns all items pushed so far in sorted order.
+    pub fn sorted(&mut self) -> &[T] {
+        self.flush();
+        &self.sorted
+    }
+
+    /// Removes and yields all items in sorted order. The sorter stays usable
+    /// and keeps its allocation.
+    pub fn drain(&mut self) -> alloc::vec::Drain<'_, T> {
+        self.flush();
+        self.sorted.drain(..)
+    }
+
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let compare = &self.compare;
+        self.pending.sort_by(|a, b| compare.compare(a, b));
+        let sorted = core::mem::take(&mut self.sorted);
+        let pending = core::mem::take(&mut self.pending);
+        self.sorted = sorted_vec::merge_sorted(sorted, pending, compare);
+    }
+
+    /// Moves the sorted items back in front of the pending ones, so a changed
+    /// comparator sorts everything again in arrival order.
+    fn reset_order(&mut self) {
+        let pending = core::mem::take(&mut self.pending);
+        self.pending = core::mem::take(&mut self.sorted);
+        self.pending.extend(pending);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OnlineSorter;
+
+    #[test]
+    fn keeps_order_across_snapshots() {
+        let mut sorter = OnlineSorter::sort_by_desc(|v: &(u32, char)| v.0);
+        sorter.push((1, 'a'));
+        sorter.push((3, 'b'));
+        assert_eq!(sorter.sorted(), &[(3, 'b'), (1, 'a')]);
+
+        sorter.push((1, 'c'));
+        sorter.push((2, 'd'));
+        sorter.push((3, 'e'));
+        assert_eq!(sorter.len(), 5);
+        assert_eq!(
+            sorter.sorted(),
+            &[(3, 'b'), (3, 'e'), (2, 'd'), (1, 'a'), (1, 'c')]
+        );
+
+        let drained: Vec<_> = sorter.drain().take(2).collect();
+        assert_eq!(drained, vec![(3, 'b'), (3, 'e')]);
+        assert!(sorter.is_empty());
+    }
+
+    #[test]
+    fn boxes_differently_chained_sorters() {
+        let mut by_id = OnlineSorter::sort_by(|v: &(u32, char)| v.0);
+        by_id.push((2, 'a'));
+        by_id.push((1, 'b'));
+        let by_char_desc = by_id.then_sort_by_desc(|v| v.1);
+
+        let mut sorters = vec![
+            OnlineSorter::sort_by_desc(|v: &(u32, char)| v.1).boxed(),
+            by_char_desc.boxed(),
+        ];
+        for sorter in &mut sorters {
+            sorter.push((1, 'c'));
+        }
+        assert_eq!(sorters[0].sorted(), &[(1, 'c')]);
+        assert_eq!(sorters[1].sorted(), &[(1, 'c'), (1, 'b'), (2, 'a')]);
+    }
+}
@@ -0,0 +1,88 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Compare, SortBy, SortError};
+
+impl<'a, I, C> SortBy<'a, I, C>
+where
+    I: Iterator,
+    C: Compare<I::Item>,
+{
+    /// Sorts the items into a `Vec`, turning a panic in a key extractor or
+    /// comparator into [`SortError::ComparatorPanicked`], e.g. for job
+    /// runners that treat panics as poison. Requires the `std` feature.
+    ///
+    /// The panic hook still runs, so the panic is reported as usual. Sorting
+    /// is panic safe either way: every item is dropped exactly once, never
+    /// duplicated or leaked.
+    ///
+    /// Like `Vec::try_from`, this fails with [`SortError::AlreadyStarted`] on
+    /// a sort that already yielded items.
+    pub fn try_into_vec(self) -> Result<Vec<I::Item>, SortError> {
+        if self.has_started() {
+            return Err(SortError::AlreadyStarted);
+        }
+        panic::catch_unwind(AssertUnwindSafe(|| self.into_vec())).map_err(|payload| {
+            let message = match payload.downcast::<String>() {
+                Ok(message) => Some(*message),
+                Err(payload) => payload.downcast_ref::<&str>().map(|m| m.to_string()),
+            };
+            SortError::ComparatorPanicked { message }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::SortError;
+    use crate::SortByIteratorExt;
+
+    struct Tracked {
+        key: u32,
+        drops: Rc<Cell<usize>>,
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn converts_panics_and_drops_every_item_once() {
+        let drops = Rc::new(Cell::new(0));
+        let items: Vec<_> = (0..100)
+            .map(|i| Tracked {
+                key: (i * 37) % 100,
+                drops: drops.clone(),
+            })
+            .collect();
+
+        let actual = items
+            .into_iter()
+            .sort_by(|v| {
+                if v.key == 42 {
+                    panic!("bad key {}", v.key);
+                }
+                v.key
+            })
+            .try_into_vec();
+
+        assert_eq!(
+            actual.err(),
+            Some(SortError::ComparatorPanicked {
+                message: Some("bad key 42".to_string())
+            })
+        );
+        assert_eq!(drops.get(), 100);
+    }
+
+    #[test]
+    fn returns_sorted_items() {
+        let actual = vec![3, 1, 2].into_iter().sort_by(|v| *v).try_into_vec();
+
+        assert_eq!(actual, Ok(vec![1, 2, 3]));
+    }
+}
@@ -0,0 +1,113 @@
+use core::cmp::Ordering;
+
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::Strategy;
+
+/// Where the parallel sorts run. Requires the `rayon` feature.
+///
+/// Services that keep CPU-heavy work on a dedicated pool pass it with
+/// [`Parallelism::Pool`], so sorting does not compete with latency sensitive
+/// tasks on rayon's global pool. Pools compare equal by identity.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Parallelism<'p> {
+    /// The global rayon thread pool.
+    #[default]
+    Global,
+    /// An existing thread pool.
+    Pool(&'p ThreadPool),
+    /// A pool with this many threads, built for the call. Falls back to
+    /// [`Parallelism::Serial`] if the pool cannot be built.
+    Threads(usize),
+    /// The calling thread only.
+    Serial,
+}
+
+impl<'p> Parallelism<'p> {
+    /// Calls `f` on every item of `items`, in parallel unless serial.
+    pub(crate) fn for_each_mut<T, F>(self, items: &mut [T], f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) + Send + Sync,
+    {
+        match self {
+            Parallelism::Global => items.par_iter_mut().for_each(f),
+            Parallelism::Pool(pool) => pool.install(|| items.par_iter_mut().for_each(f)),
+            Parallelism::Threads(threads) => {
+                match ThreadPoolBuilder::new().num_threads(threads).build() {
+                    Ok(pool) => pool.install(|| items.par_iter_mut().for_each(f)),
+                    Err(_) => items.iter_mut().for_each(f),
+                }
+            }
+            Parallelism::Serial => items.iter_mut().for_each(f),
+        }
+    }
+
+    /// Sorts `items` with `strategy`, in parallel unless serial.
+    pub(crate) fn sort_by<T, C>(self, items: &mut [T], strategy: Strategy, compare: C)
+    where
+        T: Send,
+        C: Fn(&T, &T) -> Ordering + Sync,
+    {
+        let par_sort = |items: &mut [T]| match strategy {
+            Strategy::Stable => items.par_sort_by(&compare),
+            Strategy::Unstable => items.par_sort_unstable_by(&compare),
+        };
+        match self {
+            Parallelism::Global => par_sort(items),
+            Parallelism::Pool(pool) => pool.install(|| par_sort(items)),
+            Parallelism::Threads(threads) => {
+                match ThreadPoolBuilder::new().num_threads(threads).build() {
+                    Ok(pool) => pool.install(|| par_sort(items)),
+                    Err(_) => Parallelism::Serial.sort_by(items, strategy, compare),
+                }
+            }
+            Parallelism::Serial => match strategy {
+                Strategy::Stable => items.sort_by(compare),
+                Strategy::Unstable => items.sort_unstable_by(compare),
+            },
+        }
+    }
+}
+
+impl<'p> PartialEq for Parallelism<'p> {
+    fn eq(&self, other: &Parallelism<'p>) -> bool {
+        match (self, other) {
+            (Parallelism::Global, Parallelism::Global) => true,
+            (Parallelism::Pool(a), Parallelism::Pool(b)) => core::ptr::eq(*a, *b),
+            (Parallelism::Threads(a), Parallelism::Threads(b)) => a == b,
+            (Parallelism::Serial, Parallelism::Serial) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<'p> Eq for Parallelism<'p> {}
+
+#[cfg(test)]
+mod tests {
+    use super::Parallelism;
+
+    #[test]
+    fn runs_on_the_given_pool() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name(|i| format!("sort-{}", i))
+            .build()
+            .unwrap();
+
+        let mut threads = [None, None, None];
+        Parallelism::Pool(&pool).for_each_mut(&mut threads, |thread| {
+            *thread = std::thread::current().name().map(String::from);
+        });
+        assert!(threads
+            .iter()
+            .all(|name| name.as_deref().unwrap().starts_with("sort-")));
+
+        let caller = std::thread::current().id();
+        let mut ids = [None, None];
+        Parallelism::Serial.for_each_mut(&mut ids, |id| *id = Some(std::thread::current().id()));
+        assert_eq!(ids, [Some(caller), Some(caller)]);
+    }
+}
@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+/// Where pinned items end up, see [`SortBy::with_pins`](crate::SortBy::with_pins).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PinMode {
+    /// Pinned items stay at their input positions and the remaining items are
+    /// sorted into the gaps around them.
+    #[default]
+    KeepPosition,
+    /// Pinned items come first, in input order, followed by the sorted rest.
+    FloatToTop,
+}
+
+/// Takes the pinned items out, sorts the rest with `sort` and puts the
+/// pinned items back according to `mode`.
+pub(crate) fn sort_with_pins<T, P, S>(items: Vec<T>, is_pinned: P, mode: PinMode, sort: S) -> Vec<T>
+where
+    P: Fn(&T) -> bool,
+    S: FnOnce(&mut Vec<T>),
+{
+    let mut slots = Vec::with_capacity(items.len());
+    let mut pinned = Vec::new();
+    let mut rest = Vec::with_capacity(items.len());
+    for item in items {
+        let pin = is_pinned(&item);
+        slots.push(pin);
+        if pin {
+            pinned.push(item);
+        } else {
+            rest.push(item);
+        }
+    }
+    sort(&mut rest);
+
+    match mode {
+        PinMode::FloatToTop => {
+            pinned.extend(rest);
+            pinned
+        }
+        PinMode::KeepPosition => {
+            let mut pinned = pinned.into_iter();
+            let mut rest = rest.into_iter();
+            slots
+                .into_iter()
+                .map(|pin| if pin { pinned.next() } else { rest.next() })
+                .map(|item| item.expect("one item per slot"))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn keeps_pinned_items_in_place() {
+        let rows = vec![(5, false), (1, true), (4, false), (9, true), (2, false)];
+
+        let actual: Vec<_> = rows
+            .into_iter()
+            .sort_by_with_pins(|v| v.1, |v| v.0)
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec![(2, false), (1, true), (4, false), (9, true), (5, false)]
+        );
+    }
+
+    #[test]
+    fn floats_pinned_items_to_top() {
+        let rows = vec![(5, false), (9, true), (4, false), (1, true)];
+
+        let actual: Vec<_> = rows
+            .into_iter()
+            .sort_by_desc(|v| v.0)
+            .with_pins(|v| v.1, PinMode::FloatToTop)
+            .collect();
+
+        assert_eq!(actual, vec![(9, true), (1, true), (5, false), (4, false)]);
+    }
+
+    #[test]
+    fn sorts_the_rest_with_the_configured_sort() {
+        let rows = vec![
+            (1, 'a', false),
+            (0, 'p', true),
+            (1, 'b', false),
+            (0, 'c', false),
+        ];
+
+        let actual: Vec<_> = rows
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by_input_order_desc()
+            .with_pins(|v| v.2, PinMode::KeepPosition)
+            .map(|v| v.1)
+            .collect();
+
+        assert_eq!(actual, vec!['c', 'p', 'b', 'a']);
+    }
+}
@@ -0,0 +1,98 @@
+use alloc::vec::Vec;
+
+/// The direction a single sort level orders its keys in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+/// One level of a composed sort, see [`SortPlan`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SortLevel {
+    pub direction: Direction,
+    /// The name given with [`SortBy::named`](crate::SortBy::named), if any.
+    pub name: Option<&'static str>,
+}
+
+/// The levels of a composed sort, most significant first, as returned by
+/// [`SortBy::describe`](crate::SortBy::describe).
+///
+/// Only the chained key levels are listed; adaptors that reorder eagerly,
+/// like pins or group aggregates, are not.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SortPlan {
+    pub levels: Vec<SortLevel>,
+}
+
+impl SortPlan {
+    pub(crate) fn push(&mut self, direction: Direction) {
+        self.levels.push(SortLevel {
+            direction,
+            name: None,
+        });
+    }
+
+    pub(crate) fn name_last(&mut self, name: &'static str) {
+        if let Some(level) = self.levels.last_mut() {
+            level.name = Some(name);
+        }
+    }
+}
+
+impl core::fmt::Display for SortPlan {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, level) in self.levels.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", then ")?;
+            }
+            match level.name {
+                Some(name) => write!(f, "by {}", name)?,
+                None => write!(f, "by key #{}", i + 1)?,
+            }
+            match level.direction {
+                Direction::Ascending => write!(f, " ascending")?,
+                Direction::Descending => write!(f, " descending")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn describes_composed_levels() {
+        let sort = [(1, "a")]
+            .iter()
+            .sort_by_desc(|v| v.0)
+            .named("age")
+            .then_sort_by(|v| v.1);
+
+        let plan = sort.describe();
+        assert_eq!(
+            plan.levels,
+            vec![
+                SortLevel {
+                    direction: Direction::Descending,
+                    name: Some("age"),
+                },
+                SortLevel {
+                    direction: Direction::Ascending,
+                    name: None,
+                },
+            ]
+        );
+        assert_eq!(
+            plan.to_string(),
+            "by age descending, then by key #2 ascending"
+        );
+        assert_eq!(
+            format!("{:?}", sort),
+            "SortBy { plan: by age descending, then by key #2 ascending, sorted: false, capacity: None, .. }"
+        );
+    }
+}
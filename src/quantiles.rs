@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Sample items per bucket used to pick the splitters. More samples even out
+/// the bucket sizes at the cost of sorting a larger sample.
+const OVERSAMPLING: usize = 32;
+
+/// Splits `items` into `k` buckets whose key ranges do not overlap, in
+/// ascending key order, see
+/// [`SortByIteratorExt::partition_by_quantiles`](crate::SortByIteratorExt::partition_by_quantiles).
+///
+/// The `k - 1` splitters are the quantiles of an evenly spaced sample of the
+/// items. Items with the same key always land in the same bucket, so
+/// buckets can end up uneven when a single key is very common.
+pub(crate) fn partition_by_quantiles<T, K, F>(items: Vec<T>, k: usize, key: F) -> Vec<Vec<T>>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    assert!(k > 0, "cannot partition into zero buckets");
+
+    let step = (items.len() / k.saturating_mul(OVERSAMPLING)).max(1);
+    let mut sample: Vec<K> = items.iter().step_by(step).map(&key).collect();
+    sample.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let splitters: Vec<&K> = (1..k)
+        .filter_map(|i| sample.get(i * sample.len() / k))
+        .collect();
+
+    let mut buckets: Vec<Vec<T>> = (0..k).map(|_| Vec::new()).collect();
+    for item in items {
+        let key = key(&item);
+        let bucket = splitters.partition_point(|splitter| **splitter <= key);
+        buckets[bucket].push(item);
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn splits_into_non_overlapping_buckets() {
+        let input: Vec<u32> = (0..1000).map(|i| (i * 7919) % 1000).collect();
+
+        let buckets = input.into_iter().partition_by_quantiles(4, |v| *v);
+
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets.iter().map(Vec::len).sum::<usize>(), 1000);
+        for bucket in &buckets {
+            assert!((200..=300).contains(&bucket.len()), "{}", bucket.len());
+        }
+        for pair in buckets.windows(2) {
+            assert!(pair[0].iter().max() < pair[1].iter().min());
+        }
+    }
+
+    #[test]
+    fn keeps_equal_keys_together() {
+        let input = vec![1, 1, 1, 1, 1, 1, 2, 3];
+
+        let buckets = input.into_iter().partition_by_quantiles(3, |v| *v);
+
+        assert_eq!(buckets.len(), 3);
+        assert!(buckets
+            .iter()
+            .any(|b| b.iter().filter(|v| **v == 1).count() == 6));
+    }
+}
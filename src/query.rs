@@ -0,0 +1,248 @@
+//! Extractors for a user-controlled `?sort=` query parameter. Requires the
+//! `axum` or `actix-web` feature.
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::{AllowedFields, ForbiddenSortKey, InvalidSortSpec, SortSpec};
+
+/// The fields an endpoint lets clients sort by, declared once per endpoint
+/// and checked by every [`SortQuery`] of it.
+pub trait SortFields {
+    /// The fields clients may sort by, see [`SortSpec::validate`].
+    fn allowed_fields() -> AllowedFields;
+
+    /// The spec used when the query has no `sort` parameter. It is not
+    /// validated, so it may sort by fields clients cannot choose.
+    fn default_spec() -> SortSpec {
+        SortSpec::default()
+    }
+}
+
+/// A [`SortSpec`] parsed from the `sort` query parameter and validated
+/// against the allowed fields of `F`. Requests with an invalid or forbidden
+/// spec are rejected with `400 Bad Request`.
+///
+/// ```
+/// use sortby::*;
+///
+/// struct People;
+///
+/// impl SortFields for People {
+///     fn allowed_fields() -> AllowedFields {
+///         AllowedFields::new().allow("age").allow("name")
+///     }
+/// }
+///
+/// // in a handler: `async fn list(sort: SortQuery<People>) -> ...`
+/// let sort = SortQuery::<People>::from_query("page=2&sort=-age,name").unwrap();
+/// assert_eq!(sort.to_string(), "-age,name");
+/// assert!(SortQuery::<People>::from_query("sort=salary").is_err());
+/// ```
+pub struct SortQuery<F> {
+    pub spec: SortSpec,
+    fields: PhantomData<fn() -> F>,
+}
+
+impl<F: SortFields> SortQuery<F> {
+    /// Parses and validates the `sort` parameter of a URL query string, or
+    /// falls back to [`SortFields::default_spec`] if there is none.
+    pub fn from_query(query: &str) -> Result<SortQuery<F>, SortQueryRejection> {
+        let sort = form_urlencoded::parse(query.as_bytes()).find(|(key, _)| key == "sort");
+        let spec = match sort {
+            Some((_, value)) => {
+                let spec: SortSpec = value.parse()?;
+                spec.validate(&F::allowed_fields())?;
+                spec
+            }
+            None => F::default_spec(),
+        };
+        Ok(SortQuery {
+            spec,
+            fields: PhantomData,
+        })
+    }
+}
+
+impl<F> Deref for SortQuery<F> {
+    type Target = SortSpec;
+
+    fn deref(&self) -> &SortSpec {
+        &self.spec
+    }
+}
+
+impl<F> core::fmt::Debug for SortQuery<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SortQuery")
+            .field("spec", &self.spec)
+            .finish()
+    }
+}
+
+/// Returned when a [`SortQuery`] cannot be extracted. Responds with
+/// `400 Bad Request` and the error message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SortQueryRejection {
+    /// The parameter is not a valid sort spec.
+    Invalid(InvalidSortSpec),
+    /// The spec sorts by a field or in a direction that is not allowed.
+    Forbidden(ForbiddenSortKey),
+}
+
+impl From<InvalidSortSpec> for SortQueryRejection {
+    fn from(error: InvalidSortSpec) -> SortQueryRejection {
+        SortQueryRejection::Invalid(error)
+    }
+}
+
+impl From<ForbiddenSortKey> for SortQueryRejection {
+    fn from(error: ForbiddenSortKey) -> SortQueryRejection {
+        SortQueryRejection::Forbidden(error)
+    }
+}
+
+impl core::fmt::Display for SortQueryRejection {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let error: &dyn core::fmt::Display = match self {
+            SortQueryRejection::Invalid(error) => error,
+            SortQueryRejection::Forbidden(error) => error,
+        };
+        write!(f, "invalid `sort` parameter: {}", error)
+    }
+}
+
+impl std::error::Error for SortQueryRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SortQueryRejection::Invalid(error) => Some(error),
+            SortQueryRejection::Forbidden(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl<F, S> axum_core::extract::FromRequestParts<S> for SortQuery<F>
+where
+    F: SortFields,
+    S: Send + Sync,
+{
+    type Rejection = SortQueryRejection;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        SortQuery::from_query(parts.uri.query().unwrap_or_default())
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum_core::response::IntoResponse for SortQueryRejection {
+    fn into_response(self) -> axum_core::response::Response {
+        use alloc::string::ToString;
+
+        (http::StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+#[cfg(feature = "actix-web")]
+impl<F: SortFields> actix_web::FromRequest for SortQuery<F> {
+    type Error = SortQueryRejection;
+    type Future = core::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        _payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        core::future::ready(SortQuery::from_query(req.query_string()))
+    }
+}
+
+#[cfg(feature = "actix-web")]
+impl actix_web::ResponseError for SortQueryRejection {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::http::StatusCode::BAD_REQUEST
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, UnknownField};
+
+    struct People;
+
+    impl SortFields for People {
+        fn allowed_fields() -> AllowedFields {
+            AllowedFields::new()
+                .allow("name")
+                .allow_only("age", Direction::Descending)
+        }
+
+        fn default_spec() -> SortSpec {
+            "id".parse().unwrap()
+        }
+    }
+
+    #[test]
+    fn parses_and_validates_the_sort_parameter() {
+        let sort = SortQuery::<People>::from_query("page=2&sort=-age%2Cname").unwrap();
+        assert_eq!(sort.to_string(), "-age,name");
+
+        let sort = SortQuery::<People>::from_query("page=2").unwrap();
+        assert_eq!(sort.spec, "id".parse().unwrap());
+
+        assert_eq!(
+            SortQuery::<People>::from_query("sort=name,,age").err(),
+            Some(SortQueryRejection::Invalid(InvalidSortSpec { index: 1 }))
+        );
+        assert_eq!(
+            SortQuery::<People>::from_query("sort=id").err(),
+            Some(SortQueryRejection::Forbidden(
+                ForbiddenSortKey::UnknownField(UnknownField { field: "id".into() })
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "axum")]
+    fn extracts_with_axum() {
+        use axum_core::extract::FromRequestParts;
+        use axum_core::response::IntoResponse;
+
+        let extract = |uri: &str| {
+            let (mut parts, _) = http::Request::builder()
+                .uri(uri)
+                .body(())
+                .unwrap()
+                .into_parts();
+            futures_executor::block_on(SortQuery::<People>::from_request_parts(&mut parts, &()))
+        };
+
+        assert_eq!(extract("/people?sort=name").unwrap().to_string(), "name");
+        let rejection = extract("/people?sort=age").unwrap_err();
+        assert_eq!(
+            rejection.into_response().status(),
+            http::StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "actix-web")]
+    fn extracts_with_actix_web() {
+        use actix_web::{FromRequest, ResponseError};
+
+        let extract = |uri: &str| {
+            let req = actix_web::test::TestRequest::with_uri(uri).to_http_request();
+            SortQuery::<People>::from_request(&req, &mut actix_web::dev::Payload::None).into_inner()
+        };
+
+        assert_eq!(extract("/people?sort=name").unwrap().to_string(), "name");
+        let rejection = extract("/people?sort=age").unwrap_err();
+        assert_eq!(
+            rejection.error_response().status(),
+            actix_web::http::StatusCode::BAD_REQUEST
+        );
+    }
+}
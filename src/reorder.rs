@@ -0,0 +1,138 @@
+use alloc::collections::BinaryHeap;
+use core::cmp::Ordering;
+
+/// Streams an almost-sorted iterator in sorted order, see
+/// [`SortByIteratorExt::reorder_within`](crate::SortByIteratorExt::reorder_within).
+pub struct ReorderWithin<I: Iterator, F, V> {
+    iter: I,
+    key: F,
+    window: usize,
+    seq: usize,
+    heap: BinaryHeap<Entry<V, I::Item>>,
+}
+
+impl<I, F, V> ReorderWithin<I, F, V>
+where
+    I: Iterator,
+    V: PartialOrd,
+{
+    pub(crate) fn new(iter: I, window: usize, key: F) -> ReorderWithin<I, F, V> {
+        ReorderWithin {
+            iter,
+            key,
+            window,
+            seq: 0,
+            heap: BinaryHeap::with_capacity(window.saturating_add(1)),
+        }
+    }
+}
+
+impl<I, F, V> Iterator for ReorderWithin<I, F, V>
+where
+    I: Iterator,
+    V: PartialOrd,
+    F: Fn(&I::Item) -> V,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.heap.len() <= self.window {
+            match self.iter.next() {
+                Some(item) => {
+                    self.heap.push(Entry {
+                        key: (self.key)(&item),
+                        seq: self.seq,
+                        item,
+                    });
+                    self.seq += 1;
+                }
+                None => break,
+            }
+        }
+        self.heap.pop().map(|entry| entry.item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.heap.len();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+// `BinaryHeap` is a max-heap, so the ordering is inverted to pop the smallest
+// key first and, among equal keys, the one that arrived first
+pub(crate) struct Entry<V, T> {
+    pub(crate) key: V,
+    pub(crate) seq: usize,
+    pub(crate) item: T,
+}
+
+impl<V: PartialOrd, T> Ord for Entry<V, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .partial_cmp(&self.key)
+            .unwrap_or(Ordering::Equal)
+            .then(other.seq.cmp(&self.seq))
+    }
+}
+
+impl<V: PartialOrd, T> PartialOrd for Entry<V, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<V: PartialOrd, T> PartialEq for Entry<V, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<V: PartialOrd, T> Eq for Entry<V, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn reorders_jittered_stream() {
+        let events = vec![
+            (2, 'a'),
+            (1, 'b'),
+            (3, 'c'),
+            (5, 'd'),
+            (4, 'e'),
+            (4, 'f'),
+            (6, 'g'),
+        ];
+
+        let actual: Vec<_> = events.into_iter().reorder_within(2, |v| v.0).collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                (1, 'b'),
+                (2, 'a'),
+                (3, 'c'),
+                (4, 'e'),
+                (4, 'f'),
+                (5, 'd'),
+                (6, 'g')
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_window_passes_items_through() {
+        let actual: Vec<_> = vec![3, 1, 2]
+            .into_iter()
+            .reorder_within(0, |v| *v)
+            .collect();
+
+        assert_eq!(actual, vec![3, 1, 2]);
+    }
+}
@@ -0,0 +1,172 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use ndarray::{Array2, ArrayView1, Axis};
+
+type RowCompare<'a, A> = Box<dyn Fn(&ArrayView1<'_, A>, &ArrayView1<'_, A>) -> Ordering + 'a>;
+
+/// A chained sort of the rows (or columns) of a 2-D array. Nothing happens
+/// until [`sort`] is called. Requires the `ndarray` feature.
+///
+/// [`sort`]: RowSortBy::sort
+pub struct RowSortBy<'s, 'a, A> {
+    array: &'s mut Array2<A>,
+    axis: Axis,
+    compare: RowCompare<'a, A>,
+}
+
+impl<'s, 'a, A: 'a> RowSortBy<'s, 'a, A> {
+    pub fn then_sort_by<F, V>(self, f: F) -> RowSortBy<'s, 'a, A>
+    where
+        V: PartialOrd,
+        F: Fn(&ArrayView1<'_, A>) -> V + 'a,
+    {
+        self.then_with(f, false)
+    }
+
+    pub fn then_sort_by_desc<F, V>(self, f: F) -> RowSortBy<'s, 'a, A>
+    where
+        V: PartialOrd,
+        F: Fn(&ArrayView1<'_, A>) -> V + 'a,
+    {
+        self.then_with(f, true)
+    }
+
+    fn then_with<F, V>(self, f: F, descending: bool) -> RowSortBy<'s, 'a, A>
+    where
+        V: PartialOrd,
+        F: Fn(&ArrayView1<'_, A>) -> V + 'a,
+    {
+        let first = self.compare;
+        RowSortBy {
+            array: self.array,
+            axis: self.axis,
+            compare: Box::new(move |a, b| {
+                first(a, b).then_with(|| compare_keys(&f, a, b, descending))
+            }),
+        }
+    }
+
+    /// Returns the order of the rows a stable sort produces, as indices into
+    /// the unsorted array.
+    pub fn permutation(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.array.len_of(self.axis)).collect();
+        indices.sort_by(|&i, &j| {
+            (self.compare)(
+                &self.array.index_axis(self.axis, i),
+                &self.array.index_axis(self.axis, j),
+            )
+        });
+        indices
+    }
+
+    /// Reorders the rows in place, stable, and returns the array.
+    pub fn sort(self) -> &'s mut Array2<A>
+    where
+        A: Clone,
+    {
+        let permutation = self.permutation();
+        *self.array = self.array.select(self.axis, &permutation);
+        self.array
+    }
+}
+
+fn compare_keys<A, F, V>(
+    f: &F,
+    a: &ArrayView1<'_, A>,
+    b: &ArrayView1<'_, A>,
+    descending: bool,
+) -> Ordering
+where
+    V: PartialOrd,
+    F: Fn(&ArrayView1<'_, A>) -> V,
+{
+    let (a, b) = (f(a), f(b));
+    let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// Chained sorting of the lanes of a 2-D array by keys computed from whole
+/// rows. Requires the `ndarray` feature.
+///
+/// `axis` is the axis whose indices are reordered: `Axis(0)` sorts the rows,
+/// `Axis(1)` the columns. The key functions get one row (or column) each.
+///
+/// ```
+/// use ndarray::{array, Axis};
+/// use sortby::*;
+///
+/// let mut samples = array![[2.0, 0.5], [1.0, 0.9], [2.0, 0.1]];
+/// samples
+///     .sort_rows_by(Axis(0), |row| row[0])
+///     .then_sort_by_desc(|row| row[1])
+///     .sort();
+/// assert_eq!(samples, array![[1.0, 0.9], [2.0, 0.5], [2.0, 0.1]]);
+/// ```
+pub trait ArraySortByExt<A> {
+    fn sort_rows_by<'a, F, V>(&mut self, axis: Axis, f: F) -> RowSortBy<'_, 'a, A>
+    where
+        A: 'a,
+        V: PartialOrd,
+        F: Fn(&ArrayView1<'_, A>) -> V + 'a;
+
+    fn sort_rows_by_desc<'a, F, V>(&mut self, axis: Axis, f: F) -> RowSortBy<'_, 'a, A>
+    where
+        A: 'a,
+        V: PartialOrd,
+        F: Fn(&ArrayView1<'_, A>) -> V + 'a;
+}
+
+impl<A> ArraySortByExt<A> for Array2<A> {
+    fn sort_rows_by<'a, F, V>(&mut self, axis: Axis, f: F) -> RowSortBy<'_, 'a, A>
+    where
+        A: 'a,
+        V: PartialOrd,
+        F: Fn(&ArrayView1<'_, A>) -> V + 'a,
+    {
+        RowSortBy {
+            array: self,
+            axis,
+            compare: Box::new(move |a, b| compare_keys(&f, a, b, false)),
+        }
+    }
+
+    fn sort_rows_by_desc<'a, F, V>(&mut self, axis: Axis, f: F) -> RowSortBy<'_, 'a, A>
+    where
+        A: 'a,
+        V: PartialOrd,
+        F: Fn(&ArrayView1<'_, A>) -> V + 'a,
+    {
+        RowSortBy {
+            array: self,
+            axis,
+            compare: Box::new(move |a, b| compare_keys(&f, a, b, true)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Axis};
+
+    use super::ArraySortByExt;
+
+    #[test]
+    fn sorts_rows_and_columns() {
+        let mut table = array![[3, 1, 2], [0, 9, 9], [3, 0, 5]];
+
+        let permutation = table
+            .sort_rows_by_desc(Axis(0), |row| row[0])
+            .then_sort_by(|row| row[1])
+            .permutation();
+        assert_eq!(permutation, vec![2, 0, 1]);
+
+        table.sort_rows_by(Axis(1), |column| column[0]).sort();
+        assert_eq!(table, array![[1, 2, 3], [9, 9, 0], [0, 5, 3]]);
+    }
+}
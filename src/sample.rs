@@ -0,0 +1,55 @@
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+/// Draws a uniform random sample of up to `k` items with reservoir sampling,
+/// reading `items` once and keeping only the sample in memory. The sample is
+/// returned in input order.
+pub(crate) fn reservoir_sample<I, R>(items: I, k: usize, rng: &mut R) -> Vec<I::Item>
+where
+    I: Iterator,
+    R: Rng + ?Sized,
+{
+    let mut reservoir = Vec::with_capacity(k.min(items.size_hint().0));
+    for (i, item) in items.enumerate() {
+        if i < k {
+            reservoir.push((i, item));
+        } else {
+            let j = rng.random_range(0..=i);
+            if j < k {
+                reservoir[j] = (i, item);
+            }
+        }
+    }
+    reservoir.sort_unstable_by_key(|(i, _)| *i);
+    reservoir.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn samples_and_sorts() {
+        let mut rng = SmallRng::seed_from_u64(7);
+
+        let sample: Vec<_> = (0..10_000)
+            .sorted_sample_by(100, |v| -v, &mut rng)
+            .collect();
+
+        assert_eq!(sample.len(), 100);
+        assert!(sample.windows(2).all(|w| w[0] > w[1]));
+        assert!(sample.iter().any(|v| *v < 5_000) && sample.iter().any(|v| *v >= 5_000));
+
+        let sample: Vec<_> = (0..10).sorted_sample_by(100, |v| *v, &mut rng).collect();
+        assert_eq!(sample, (0..10).collect::<Vec<_>>());
+
+        let sample: Vec<_> = (0..3)
+            .sorted_sample_by(usize::MAX, |v| *v, &mut rng)
+            .collect();
+        assert_eq!(sample, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{ColumnSortState, InvalidSortSpec, SortKey, SortSpec};
+
+/// The version [`SortSpec::save`] writes. Saved specs of this and every
+/// earlier version can be restored.
+pub const SAVED_SPEC_VERSION: u32 = 1;
+
+/// Field renames and removals applied when restoring a saved sort order, so
+/// user preferences survive schema changes.
+///
+/// Renames apply in the order they were added, so renaming `a` to `b` and
+/// later `b` to `c` restores a saved `a` as `c`. Keys of removed fields are
+/// dropped, as are later duplicates of a field.
+///
+/// ```
+/// use sortby::*;
+///
+/// let saved = "v1:-created,legacy_rank,name";
+/// let migrations = Migrations::new()
+///     .rename("created", "created_at")
+///     .remove("legacy_rank");
+///
+/// let spec = SortSpec::restore(saved, &migrations).unwrap();
+/// assert_eq!(spec.to_string(), "-created_at,name");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Migrations {
+    steps: Vec<(String, Option<String>)>,
+}
+
+impl Migrations {
+    pub fn new() -> Migrations {
+        Migrations::default()
+    }
+
+    pub fn rename(mut self, from: &str, to: &str) -> Migrations {
+        self.steps.push((from.to_string(), Some(to.to_string())));
+        self
+    }
+
+    pub fn remove(mut self, field: &str) -> Migrations {
+        self.steps.push((field.to_string(), None));
+        self
+    }
+
+    fn apply(&self, field: String) -> Option<String> {
+        self.steps.iter().try_fold(field, |field, (from, to)| {
+            if *from == field {
+                to.clone()
+            } else {
+                Some(field)
+            }
+        })
+    }
+}
+
+impl SortSpec {
+    /// Serializes the spec into a versioned string, e.g. `v1:-age,name`, to
+    /// persist it across sessions. Restore it with [`SortSpec::restore`].
+    pub fn save(&self) -> String {
+        alloc::format!("v{}:{}", SAVED_SPEC_VERSION, self)
+    }
+
+    /// Restores a spec written by [`SortSpec::save`], applying `migrations`
+    /// to its field names.
+    pub fn restore(saved: &str, migrations: &Migrations) -> Result<SortSpec, InvalidSavedSpec> {
+        let (version, spec) = saved
+            .strip_prefix('v')
+            .and_then(|saved| saved.split_once(':'))
+            .ok_or(InvalidSavedSpec::MissingVersion)?;
+        match version.parse() {
+            Ok(version) if (1..=SAVED_SPEC_VERSION).contains(&version) => {}
+            _ => return Err(InvalidSavedSpec::UnsupportedVersion(version.to_string())),
+        }
+
+        let spec: SortSpec = spec.parse().map_err(InvalidSavedSpec::Spec)?;
+        let mut keys: Vec<SortKey> = Vec::with_capacity(spec.keys.len());
+        for mut key in spec.keys {
+            if let Some(field) = migrations.apply(key.field) {
+                if keys.iter().all(|k| k.field != field) {
+                    key.field = field;
+                    keys.push(key);
+                }
+            }
+        }
+        Ok(SortSpec { keys })
+    }
+}
+
+impl ColumnSortState {
+    /// Serializes the state like [`SortSpec::save`].
+    pub fn save(&self) -> String {
+        self.spec().save()
+    }
+
+    /// Restores a state written by [`ColumnSortState::save`], see
+    /// [`SortSpec::restore`].
+    pub fn restore(
+        saved: &str,
+        migrations: &Migrations,
+    ) -> Result<ColumnSortState, InvalidSavedSpec> {
+        SortSpec::restore(saved, migrations).map(ColumnSortState::from)
+    }
+}
+
+/// Returned by [`SortSpec::restore`] for strings it cannot read.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvalidSavedSpec {
+    /// The string does not start with a `v<version>:` prefix.
+    MissingVersion,
+    /// The string was written by a newer version of this crate.
+    UnsupportedVersion(String),
+    Spec(InvalidSortSpec),
+}
+
+impl core::fmt::Display for InvalidSavedSpec {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InvalidSavedSpec::MissingVersion => write!(f, "saved sort order has no version"),
+            InvalidSavedSpec::UnsupportedVersion(version) => {
+                write!(f, "unsupported saved sort order version `{}`", version)
+            }
+            InvalidSavedSpec::Spec(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidSavedSpec {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_migrates() {
+        let mut state = ColumnSortState::new();
+        state.click("b");
+        state.shift_click("a");
+        state.shift_click("a");
+        let saved = state.save();
+        assert_eq!(saved, "v1:b,-a");
+
+        let restored = ColumnSortState::restore(&saved, &Migrations::new()).unwrap();
+        assert_eq!(restored, state);
+
+        let migrations = Migrations::new().rename("a", "x").rename("x", "b");
+        let restored = SortSpec::restore(&saved, &migrations).unwrap();
+        assert_eq!(restored.to_string(), "b");
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let none = Migrations::new();
+
+        assert_eq!(
+            SortSpec::restore("-age", &none),
+            Err(InvalidSavedSpec::MissingVersion)
+        );
+        assert_eq!(
+            SortSpec::restore("v2:-age", &none),
+            Err(InvalidSavedSpec::UnsupportedVersion("2".to_string()))
+        );
+        assert_eq!(
+            SortSpec::restore("v1:age,,name", &none),
+            Err(InvalidSavedSpec::Spec(InvalidSortSpec { index: 1 }))
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{trace, SortError};
+
+/// Merges locally sorted pages, e.g. the responses of a scatter-gather
+/// search over several shards, into the first `limit` items of the global
+/// order. `compare` must be the comparator every shard sorted its page with,
+/// e.g. one built by [`SortSpec::compare_fn`](crate::SortSpec::compare_fn)
+/// and passed as `&*compare`.
+///
+/// The pages are merged with a heap over their heads in `O(limit log k)` for
+/// `k` pages. On ties, items of earlier pages come first.
+///
+/// Fails if a page is not ordered by `compare`.
+///
+/// ```
+/// use sortby::*;
+///
+/// let pages = vec![vec![1, 4, 7], vec![2, 3, 9], vec![5, 6]];
+/// let top = merge_shards(pages, cmp::by_key(|v: &i32| *v), 4).unwrap();
+/// assert_eq!(top, vec![1, 2, 3, 4]);
+/// ```
+pub fn merge_shards<T, C, P>(pages: P, compare: C, limit: usize) -> Result<Vec<T>, SortError>
+where
+    C: Fn(&T, &T) -> Ordering,
+    P: IntoIterator<Item = Vec<T>>,
+{
+    let mut shards = Vec::new();
+    for (input, page) in pages.into_iter().enumerate() {
+        if let Some(index) =
+            (1..page.len()).find(|&i| compare(&page[i - 1], &page[i]) == Ordering::Greater)
+        {
+            return Err(SortError::IncompatibleOrder { input, index });
+        }
+        shards.push(page.into_iter());
+    }
+
+    let mut span = trace::merge::<T>(shards.len());
+    let mut heap = BinaryHeap::with_capacity(shards.len());
+    for (shard, page) in shards.iter_mut().enumerate() {
+        if let Some(item) = page.next() {
+            heap.push(Head {
+                item,
+                shard,
+                compare: &compare,
+            });
+        }
+    }
+
+    let total = heap.len() + shards.iter().map(|page| page.len()).sum::<usize>();
+    let mut merged = Vec::with_capacity(limit.min(total));
+    while merged.len() < limit {
+        let Some(Head { item, shard, .. }) = heap.pop() else {
+            break;
+        };
+        merged.push(item);
+        if let Some(item) = shards[shard].next() {
+            heap.push(Head {
+                item,
+                shard,
+                compare: &compare,
+            });
+        }
+    }
+    span.record_items(merged.len());
+    Ok(merged)
+}
+
+/// The current head of a page in the merge heap. `BinaryHeap` is a max-heap,
+/// so the order is reversed to pop the smallest item, and the earliest shard
+/// among equal ones.
+struct Head<'c, T, C> {
+    item: T,
+    shard: usize,
+    compare: &'c C,
+}
+
+impl<'c, T, C: Fn(&T, &T) -> Ordering> Ord for Head<'c, T, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.compare)(&other.item, &self.item).then(other.shard.cmp(&self.shard))
+    }
+}
+
+impl<'c, T, C: Fn(&T, &T) -> Ordering> PartialOrd for Head<'c, T, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'c, T, C: Fn(&T, &T) -> Ordering> PartialEq for Head<'c, T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'c, T, C: Fn(&T, &T) -> Ordering> Eq for Head<'c, T, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_shards;
+    use crate::{cmp, SortError};
+
+    #[test]
+    fn merges_pages_up_to_limit() {
+        let pages = vec![
+            vec![(9, 'a'), (5, 'a'), (1, 'a')],
+            vec![],
+            vec![(8, 'c'), (5, 'c'), (2, 'c')],
+        ];
+
+        let actual = merge_shards(pages.clone(), cmp::by_key_desc(|v: &(i32, char)| v.0), 4);
+        assert_eq!(actual, Ok(vec![(9, 'a'), (8, 'c'), (5, 'a'), (5, 'c')]));
+
+        let actual = merge_shards(pages, cmp::by_key_desc(|v: &(i32, char)| v.0), 100);
+        assert_eq!(actual.unwrap().len(), 6);
+    }
+
+    #[test]
+    fn rejects_unsorted_pages() {
+        let pages = vec![vec![1, 2], vec![3, 5, 4]];
+
+        let actual = merge_shards(pages, cmp::by_key(|v: &i32| *v), 3);
+        assert_eq!(
+            actual,
+            Err(SortError::IncompatibleOrder { input: 1, index: 2 })
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+/// Yields the items of an iterator in random order, see
+/// [`SortByIteratorExt::shuffle`](crate::SortByIteratorExt::shuffle).
+pub struct Shuffle<'r, I: Iterator, R: ?Sized> {
+    iter: Option<I>,
+    buffer: Vec<I::Item>,
+    rng: &'r mut R,
+}
+
+impl<'r, I, R> Shuffle<'r, I, R>
+where
+    I: Iterator,
+    R: ?Sized,
+{
+    pub(crate) fn new(iter: I, rng: &'r mut R) -> Shuffle<'r, I, R> {
+        Shuffle {
+            iter: Some(iter),
+            buffer: Vec::new(),
+            rng,
+        }
+    }
+}
+
+impl<'r, I, R> Iterator for Shuffle<'r, I, R>
+where
+    I: Iterator,
+    R: Rng + ?Sized,
+{
+    type Item = I::Item;
+
+    /// Picks a uniformly random item among the remaining ones, so taking only
+    /// the first `n` items costs `n` random draws rather than a full shuffle.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(iter) = self.iter.take() {
+            self.buffer = iter.collect();
+        }
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let index = self.rng.random_range(0..self.buffer.len());
+        Some(self.buffer.swap_remove(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.iter {
+            Some(ref iter) => iter.size_hint(),
+            None => (self.buffer.len(), Some(self.buffer.len())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn shuffles_all_items() {
+        let mut rng = SmallRng::seed_from_u64(7);
+
+        let mut shuffled: Vec<_> = (0..100).shuffle(&mut rng).collect();
+        assert_ne!(shuffled, (0..100).collect::<Vec<_>>());
+        shuffled.sort();
+        assert_eq!(shuffled, (0..100).collect::<Vec<_>>());
+
+        let mut picked: Vec<_> = (0..100).shuffle(&mut rng).take(3).collect();
+        picked.dedup();
+        assert_eq!(picked.len(), 3);
+    }
+}
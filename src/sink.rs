@@ -0,0 +1,177 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+
+use crate::{ByKey, ByKeyDesc, Compare, CompareFn, Then};
+
+/// Collects items from any number of `extend` calls or pushes and sorts
+/// them once when finalized, e.g. for push-style pipelines where the
+/// producer drives the loop.
+///
+/// Collecting with [`FromIterator`] sorts by the natural order of `T`; the
+/// constructors take a key chain like [`SortByIteratorExt`](crate::SortByIteratorExt).
+/// The sort is stable, so equal items keep the order they arrived in.
+///
+/// ```
+/// use sortby::SortedSink;
+///
+/// let mut sink = SortedSink::sort_by_desc(|v: &(u32, char)| v.0);
+/// sink.extend(vec![(1, 'a'), (3, 'b')]);
+/// sink.extend(vec![(2, 'c'), (3, 'd')]);
+/// assert_eq!(sink.into_sorted_vec(), vec![(3, 'b'), (3, 'd'), (2, 'c'), (1, 'a')]);
+///
+/// let sink: SortedSink<_> = vec![3, 1, 2].into_iter().collect();
+/// assert_eq!(sink.into_sorted_vec(), vec![1, 2, 3]);
+/// ```
+///
+/// Like [`SortBy`](crate::SortBy), the comparator `C` is the concrete chain
+/// of sort levels; [`boxed`](SortedSink::boxed) erases it into a
+/// [`CompareFn`] when the type has to be named or stored.
+pub struct SortedSink<'a, T, C = CompareFn<'a, T>> {
+    compare: C,
+    items: Vec<T>,
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a, T> SortedSink<'a, T> {
+    pub fn sort_by<F, V>(f: F) -> SortedSink<'a, T, ByKey<F, V>>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V,
+    {
+        SortedSink::new(ByKey::new(f))
+    }
+
+    pub fn sort_by_desc<F, V>(f: F) -> SortedSink<'a, T, ByKeyDesc<F, V>>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V,
+    {
+        SortedSink::new(ByKeyDesc::new(f))
+    }
+}
+
+impl<'a, T, C: Compare<T>> SortedSink<'a, T, C> {
+    fn new(compare: C) -> SortedSink<'a, T, C> {
+        SortedSink {
+            compare,
+            items: Vec::new(),
+            lifetime: PhantomData,
+        }
+    }
+
+    pub fn then_sort_by<G, U>(self, f: G) -> SortedSink<'a, T, Then<C, ByKey<G, U>>>
+    where
+        U: PartialOrd,
+        G: Fn(&T) -> U,
+    {
+        self.then_with(ByKey::new(f))
+    }
+
+    pub fn then_sort_by_desc<G, U>(self, f: G) -> SortedSink<'a, T, Then<C, ByKeyDesc<G, U>>>
+    where
+        U: PartialOrd,
+        G: Fn(&T) -> U,
+    {
+        self.then_with(ByKeyDesc::new(f))
+    }
+
+    fn then_with<D: Compare<T>>(self, next: D) -> SortedSink<'a, T, Then<C, D>> {
+        SortedSink {
+            compare: Then::new(self.compare, next),
+            items: self.items,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Erases the comparator chain into a boxed [`CompareFn`].
+    pub fn boxed(self) -> SortedSink<'a, T>
+    where
+        C: Send + Sync + 'a,
+    {
+        SortedSink {
+            compare: self.compare.into_compare_fn(),
+            items: self.items,
+            lifetime: PhantomData,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Sorts everything collected so far.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let compare = &self.compare;
+        self.items.sort_by(|a, b| compare.compare(a, b));
+        self.items
+    }
+}
+
+impl<'a, T, C> Extend<T> for SortedSink<'a, T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+impl<'a, T: Ord + 'a> FromIterator<T> for SortedSink<'a, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let compare: CompareFn<'a, T> = Arc::new(|a: &T, b: &T| a.cmp(b));
+        let mut sink = SortedSink::new(compare);
+        sink.extend(iter);
+        sink
+    }
+}
+
+impl<'a, T, C: Compare<T>> From<SortedSink<'a, T, C>> for Vec<T> {
+    fn from(sink: SortedSink<'a, T, C>) -> Self {
+        sink.into_sorted_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SortedSink;
+
+    #[test]
+    fn sorts_items_from_several_sources() {
+        let mut sink = SortedSink::sort_by(|v: &(u32, char)| v.0).then_sort_by_desc(|v| v.1);
+        sink.extend(vec![(2, 'a'), (1, 'b')]);
+        sink.push((2, 'c'));
+        sink.extend(core::iter::once((1, 'd')));
+
+        assert_eq!(sink.len(), 4);
+        assert_eq!(
+            Vec::from(sink),
+            vec![(1, 'd'), (1, 'b'), (2, 'c'), (2, 'a')]
+        );
+    }
+
+    #[test]
+    fn boxes_differently_chained_sinks() {
+        let mut sinks = vec![
+            SortedSink::sort_by(|v: &(u32, char)| v.0).boxed(),
+            SortedSink::sort_by_desc(|v: &(u32, char)| v.1)
+                .then_sort_by(|v| v.0)
+                .boxed(),
+        ];
+        for sink in &mut sinks {
+            sink.extend(vec![(2, 'b'), (1, 'a')]);
+        }
+
+        let sorted: Vec<_> = sinks.into_iter().map(Vec::from).collect();
+        assert_eq!(
+            sorted,
+            vec![vec![(1, 'a'), (2, 'b')], vec![(2, 'b'), (1, 'a')]]
+        );
+    }
+}
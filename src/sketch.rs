@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+
+/// Error bound [`SortByIteratorExt::approx_quantiles_by`] uses: returned
+/// values are within 0.1% of the stream length of the exact rank.
+///
+/// [`SortByIteratorExt::approx_quantiles_by`]: crate::SortByIteratorExt::approx_quantiles_by
+pub const DEFAULT_EPSILON: f64 = 0.001;
+
+/// A Greenwald-Khanna quantile sketch. It answers quantile queries over a
+/// stream with a rank error of at most `epsilon * len` while keeping only
+/// `O(log(epsilon * len) / epsilon)` values. Requires the `sketch` feature.
+///
+/// ```
+/// use sortby::QuantileSketch;
+///
+/// let mut sketch = QuantileSketch::new(0.01);
+/// for latency in (0..10_000).rev() {
+///     sketch.insert(latency);
+/// }
+/// let p99 = *sketch.quantile(0.99).unwrap();
+/// assert!((9_800..=10_000).contains(&p99));
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuantileSketch<K> {
+    epsilon: f64,
+    len: usize,
+    /// Sorted values, each with the rank gap `g` to its predecessor and the
+    /// uncertainty `delta` of its rank.
+    tuples: Vec<Tuple<K>>,
+}
+
+#[derive(Clone, Debug)]
+struct Tuple<K> {
+    value: K,
+    g: usize,
+    delta: usize,
+}
+
+impl<K: PartialOrd> QuantileSketch<K> {
+    /// # Panics
+    ///
+    /// Panics unless `0 < epsilon < 1`.
+    pub fn new(epsilon: f64) -> QuantileSketch<K> {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon must be between 0 and 1"
+        );
+        QuantileSketch {
+            epsilon,
+            len: 0,
+            tuples: Vec::new(),
+        }
+    }
+
+    /// Number of values inserted so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, value: K) {
+        let index = self.tuples.partition_point(|tuple| tuple.value <= value);
+        let delta = if index == 0 || index == self.tuples.len() {
+            0
+        } else {
+            self.band()
+        };
+        self.tuples.insert(index, Tuple { value, g: 1, delta });
+        self.len += 1;
+
+        let period = (1.0 / (2.0 * self.epsilon)) as usize;
+        if self.len % period.max(1) == 0 {
+            self.compress();
+        }
+    }
+
+    /// Returns a value whose rank is within `epsilon * len` of the rank
+    /// `q * len`, or `None` if nothing was inserted. `q` is clamped to
+    /// `0..=1`.
+    pub fn quantile(&self, q: f64) -> Option<&K> {
+        let exact = q.clamp(0.0, 1.0) * self.len as f64;
+        // `f64::ceil` needs std
+        let rank = exact as usize + usize::from((exact as usize as f64) < exact);
+        let bound = rank + (self.epsilon * self.len as f64) as usize;
+        let mut min_rank = 0;
+        let mut previous = self.tuples.first()?;
+        for tuple in &self.tuples {
+            min_rank += tuple.g;
+            if min_rank + tuple.delta > bound {
+                return Some(&previous.value);
+            }
+            previous = tuple;
+        }
+        Some(&previous.value)
+    }
+
+    /// The rank uncertainty a tuple may have without breaking the error bound.
+    fn band(&self) -> usize {
+        (2.0 * self.epsilon * self.len as f64) as usize
+    }
+
+    /// Merges neighboring tuples whose combined rank range stays within the
+    /// error bound. The first and last tuple are kept, so the exact minimum
+    /// and maximum are always known.
+    fn compress(&mut self) {
+        let band = self.band();
+        let mut i = self.tuples.len().saturating_sub(2);
+        while i >= 1 {
+            let (g, next) = (self.tuples[i].g, &self.tuples[i + 1]);
+            if g + next.g + next.delta <= band {
+                self.tuples[i + 1].g += g;
+                self.tuples.remove(i);
+            }
+            i -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QuantileSketch;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn stays_within_error_bound() {
+        let len: i64 = 100_000;
+        let input = (0..len).map(|i| (i * 7919) % len);
+
+        let mut sketch = QuantileSketch::new(0.001);
+        input.clone().for_each(|v| sketch.insert(v));
+        assert_eq!(sketch.len(), len as usize);
+        assert!(sketch.tuples.len() < 5_000, "{}", sketch.tuples.len());
+
+        for q in [0.0, 0.25, 0.5, 0.95, 0.99, 1.0] {
+            let exact = (q * len as f64).ceil() as i64 - 1;
+            let actual = *sketch.quantile(q).unwrap();
+            assert!((actual - exact.max(0)).abs() <= 100, "{} {}", q, actual);
+        }
+
+        let quantiles = input.approx_quantiles_by(|v| *v, &[0.5, 0.99]).unwrap();
+        assert!((quantiles[0] - 50_000).abs() <= 100);
+        assert!((quantiles[1] - 99_000).abs() <= 100);
+    }
+
+    #[test]
+    fn returns_none_for_empty_streams() {
+        let sketch = QuantileSketch::<u32>::new(0.01);
+        assert_eq!(sketch.quantile(0.5), None);
+        assert_eq!(
+            core::iter::empty::<u32>().approx_quantiles_by(|v| *v, &[0.5]),
+            None
+        );
+    }
+}
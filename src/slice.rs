@@ -0,0 +1,131 @@
+use crate::{
+    compare_by, compare_by_desc, then_compare_by, then_compare_by_desc, CompareFn, Strategy,
+};
+
+/// A chained sort over a mutable slice. Nothing happens until [`sort`] is
+/// called.
+///
+/// [`sort`]: SliceSortBy::sort
+pub struct SliceSortBy<'s, 'a, T> {
+    slice: &'s mut [T],
+    compare: CompareFn<'a, T>,
+    strategy: Strategy,
+}
+
+impl<'s, 'a, T> SliceSortBy<'s, 'a, T> {
+    pub(crate) fn new(slice: &'s mut [T], compare: CompareFn<'a, T>) -> SliceSortBy<'s, 'a, T> {
+        SliceSortBy {
+            slice,
+            compare,
+            strategy: Strategy::default(),
+        }
+    }
+}
+
+impl<'s, 'a, T: 'a> SliceSortBy<'s, 'a, T> {
+    pub fn then_sort_by<F, V>(self, f: F) -> SliceSortBy<'s, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        SliceSortBy {
+            slice: self.slice,
+            compare: then_compare_by(self.compare, f),
+            strategy: self.strategy,
+        }
+    }
+
+    pub fn then_sort_by_desc<F, V>(self, f: F) -> SliceSortBy<'s, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        SliceSortBy {
+            slice: self.slice,
+            compare: then_compare_by_desc(self.compare, f),
+            strategy: self.strategy,
+        }
+    }
+
+    /// Gives up the stable order of equal items for a faster sort.
+    pub fn unstable(mut self) -> SliceSortBy<'s, 'a, T> {
+        self.strategy = Strategy::Unstable;
+        self
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.strategy == Strategy::Stable
+    }
+
+    /// Sorts the slice in place, stable unless [`unstable`] was called, and
+    /// returns it.
+    ///
+    /// [`unstable`]: SliceSortBy::unstable
+    pub fn sort(self) -> &'s mut [T] {
+        let compare = self.compare;
+        match self.strategy {
+            Strategy::Stable => self.slice.sort_by(|a, b| compare(a, b)),
+            Strategy::Unstable => self.slice.sort_unstable_by(|a, b| compare(a, b)),
+        }
+        self.slice
+    }
+}
+
+pub trait SliceSortByExt<T> {
+    fn sort_by_key_chain<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a;
+
+    fn sort_by_key_chain_desc<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a;
+}
+
+impl<T> SliceSortByExt<T> for [T] {
+    fn sort_by_key_chain<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        SliceSortBy::new(self, compare_by(f))
+    }
+
+    fn sort_by_key_chain_desc<'a, F, V>(&mut self, f: F) -> SliceSortBy<'_, 'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        SliceSortBy::new(self, compare_by_desc(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_slice_in_place() {
+        let mut data = [(1, "b"), (0, "z"), (1, "a"), (0, "y")];
+
+        let sorted = data[..]
+            .sort_by_key_chain_desc(|v| v.0)
+            .then_sort_by(|v| v.1)
+            .sort();
+        assert_eq!(sorted, &[(1, "a"), (1, "b"), (0, "y"), (0, "z")]);
+
+        assert_eq!(data, [(1, "a"), (1, "b"), (0, "y"), (0, "z")]);
+    }
+
+    #[test]
+    fn opts_out_of_stability() {
+        let mut data = [3, 1, 2];
+
+        let sort = data[..].sort_by_key_chain(|v| *v);
+        assert!(sort.is_stable());
+        let sort = sort.unstable();
+        assert!(!sort.is_stable());
+        assert_eq!(sort.sort(), &[1, 2, 3]);
+    }
+}
@@ -0,0 +1,363 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::{Bound, Deref, RangeBounds};
+
+use crate::{trace, Compare, CompareFn, SortError};
+
+/// A `Vec` that is kept in the order of the comparator it was sorted with.
+///
+/// Created by [`SortBy::into_sorted_vec`](crate::SortBy::into_sorted_vec).
+/// Only read access is handed out, so the order can't be broken from the
+/// outside.
+pub struct SortedVec<'a, T> {
+    items: Vec<T>,
+    compare: CompareFn<'a, T>,
+}
+
+impl<'a, T> SortedVec<'a, T> {
+    /// `items` must already be sorted by `compare`.
+    pub(crate) fn from_sorted(items: Vec<T>, compare: CompareFn<'a, T>) -> SortedVec<'a, T> {
+        SortedVec { items, compare }
+    }
+
+    /// Looks for an item that compares equal to `item`, see
+    /// [`slice::binary_search`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search).
+    pub fn binary_search(&self, item: &T) -> Result<usize, usize> {
+        self.items
+            .binary_search_by(|probe| (self.compare)(probe, item))
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.binary_search(item).is_ok()
+    }
+
+    /// Inserts `item` behind all items that compare equal to it and returns
+    /// its index.
+    pub fn insert_sorted(&mut self, item: T) -> usize {
+        let index = self
+            .items
+            .partition_point(|probe| (self.compare)(probe, &item) != Ordering::Greater);
+        self.items.insert(index, item);
+        index
+    }
+
+    /// Merges the items of `other` into `self` in linear time. On ties, the
+    /// items of `self` come first.
+    ///
+    /// Fails if `other` is not ordered by the comparator of `self`.
+    pub fn merge(self, other: SortedVec<'_, T>) -> Result<SortedVec<'a, T>, SortError> {
+        self.merge_all(core::iter::once(other))
+    }
+
+    /// Merges any number of sorted vecs into `self`, pairwise in rounds so
+    /// the total work is `O(n log k)` for `k` inputs. On ties, items keep the
+    /// order of the inputs with `self` first.
+    ///
+    /// Fails if one of the inputs is not ordered by the comparator of `self`.
+    pub fn merge_all<'b, I>(self, others: I) -> Result<SortedVec<'a, T>, SortError>
+    where
+        I: IntoIterator<Item = SortedVec<'b, T>>,
+        T: 'b,
+    {
+        let compare = self.compare;
+        let mut runs = alloc::vec![self.items];
+        for (input, other) in others.into_iter().enumerate() {
+            if let Some(index) = (1..other.items.len())
+                .find(|&i| compare(&other.items[i - 1], &other.items[i]) == Ordering::Greater)
+            {
+                return Err(SortError::IncompatibleOrder { input, index });
+            }
+            runs.push(other.items);
+        }
+
+        let mut span = trace::merge::<T>(runs.len());
+        span.record_items(runs.iter().map(Vec::len).sum());
+        while runs.len() > 1 {
+            let mut next = Vec::with_capacity(runs.len().div_ceil(2));
+            let mut runs_iter = runs.into_iter();
+            while let Some(left) = runs_iter.next() {
+                match runs_iter.next() {
+                    Some(right) => next.push(merge_sorted(left, right, &compare)),
+                    None => next.push(left),
+                }
+            }
+            runs = next;
+        }
+
+        Ok(SortedVec {
+            items: runs.pop().unwrap_or_default(),
+            compare,
+        })
+    }
+
+    /// Returns the items whose key falls into `range`.
+    ///
+    /// `key` must be the primary level of the sort order, either ascending or
+    /// descending, so that matching items are adjacent.
+    pub fn range_by_key<R, K, F>(&self, range: R, key: F) -> &[T]
+    where
+        R: RangeBounds<K>,
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        let (start, end) = key_bounds(&self.items, &range, &key);
+        &self.items[start..end]
+    }
+
+    /// Returns the index of the first item whose key equals `value`. The same
+    /// requirements as for [`range_by_key`](SortedVec::range_by_key) apply.
+    pub fn position_of_key<K, F>(&self, value: &K, key: F) -> Option<usize>
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        let range = (Bound::Included(value), Bound::Included(value));
+        let (start, end) = key_bounds(&self.items, &range, &key);
+        if start < end {
+            Some(start)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the items that sort after an item with key `boundary`, e.g.
+    /// the last item of a previously returned page. As with
+    /// [`range_by_key`](SortedVec::range_by_key), `key` must follow the sort
+    /// order; wrap descending levels of a tuple key in `std::cmp::Reverse`.
+    pub fn skip_after_key<K, F>(&self, boundary: &K, key: F) -> &[T]
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        &self.items[skip_after_key_index(&self.items, boundary, &key)..]
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+fn key_bounds<T, R, K, F>(items: &[T], range: &R, key: &F) -> (usize, usize)
+where
+    R: RangeBounds<K>,
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    let below = |item: &T| match range.start_bound() {
+        Bound::Included(start) => key(item) < *start,
+        Bound::Excluded(start) => key(item) <= *start,
+        Bound::Unbounded => false,
+    };
+    let above = |item: &T| match range.end_bound() {
+        Bound::Included(end) => key(item) > *end,
+        Bound::Excluded(end) => key(item) >= *end,
+        Bound::Unbounded => false,
+    };
+
+    let (start, end) = if is_descending(items, key) {
+        (
+            items.partition_point(|item| above(item)),
+            items.partition_point(|item| !below(item)),
+        )
+    } else {
+        (
+            items.partition_point(|item| below(item)),
+            items.partition_point(|item| !above(item)),
+        )
+    };
+    (start, end.max(start))
+}
+
+/// Index of the first item that sorts after an item with key `boundary`.
+pub(crate) fn skip_after_key_index<T, K, F>(items: &[T], boundary: &K, key: &F) -> usize
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    if is_descending(items, key) {
+        items.partition_point(|item| key(item) >= *boundary)
+    } else {
+        items.partition_point(|item| key(item) <= *boundary)
+    }
+}
+
+fn is_descending<T, K, F>(items: &[T], key: &F) -> bool
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    match (items.first(), items.last()) {
+        (Some(first), Some(last)) => key(first) > key(last),
+        _ => false,
+    }
+}
+
+pub(crate) fn merge_sorted<T, C: Compare<T>>(left: Vec<T>, right: Vec<T>, compare: &C) -> Vec<T> {
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    loop {
+        let take_left = match (left.peek(), right.peek()) {
+            (Some(a), Some(b)) => compare.compare(a, b) != Ordering::Greater,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => return merged,
+        };
+        if take_left {
+            merged.extend(left.next());
+        } else {
+            merged.extend(right.next());
+        }
+    }
+}
+
+impl<'a, T> Deref for SortedVec<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<'a, T> From<SortedVec<'a, T>> for Vec<T> {
+    fn from(val: SortedVec<'a, T>) -> Self {
+        val.items
+    }
+}
+
+impl<'a, T> IntoIterator for SortedVec<'a, T> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'s, 'a, T> IntoIterator for &'s SortedVec<'a, T> {
+    type Item = &'s T;
+    type IntoIter = core::slice::Iter<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn searches_with_the_sort_order() {
+        let sorted = vec![5, 1, 9, 3]
+            .into_iter()
+            .sort_by_desc(|v| *v)
+            .into_sorted_vec();
+
+        assert_eq!(sorted.as_slice(), &[9, 5, 3, 1]);
+        assert_eq!(sorted.binary_search(&3), Ok(2));
+        assert_eq!(sorted.binary_search(&4), Err(2));
+        assert!(sorted.contains(&9));
+        assert!(!sorted.contains(&2));
+    }
+
+    #[test]
+    fn inserts_behind_equal_items() {
+        let mut sorted = vec![(1, 'a'), (2, 'b')]
+            .into_iter()
+            .sort_by(|v| v.0)
+            .into_sorted_vec();
+
+        assert_eq!(sorted.insert_sorted((1, 'c')), 1);
+        assert_eq!(sorted.insert_sorted((0, 'd')), 0);
+        assert_eq!(
+            sorted.into_vec(),
+            vec![(0, 'd'), (1, 'a'), (1, 'c'), (2, 'b')]
+        );
+    }
+
+    #[test]
+    fn finds_items_by_key_range() {
+        let ages = vec![30, 18, 9, 21, 18, 40];
+        let asc = ages.clone().into_iter().sort_by(|v| *v).into_sorted_vec();
+        let desc = ages.into_iter().sort_by_desc(|v| *v).into_sorted_vec();
+
+        assert_eq!(asc.range_by_key(18..=21, |v| *v), &[18, 18, 21]);
+        assert_eq!(asc.range_by_key(18..21, |v| *v), &[18, 18]);
+        assert_eq!(asc.range_by_key(..10, |v| *v), &[9]);
+        assert_eq!(desc.range_by_key(18..=21, |v| *v), &[21, 18, 18]);
+        assert_eq!(desc.range_by_key(31.., |v| *v), &[40]);
+        assert!(asc.range_by_key(22..30, |v| *v).is_empty());
+
+        assert_eq!(asc.position_of_key(&18, |v| *v), Some(1));
+        assert_eq!(desc.position_of_key(&18, |v| *v), Some(3));
+        assert_eq!(asc.position_of_key(&19, |v| *v), None);
+    }
+
+    #[test]
+    fn skips_past_boundary_key() {
+        let people = vec![(30, "d"), (18, "b"), (21, "c"), (18, "a"), (40, "e")];
+        let sorted = people
+            .into_iter()
+            .sort_by_desc(|v| v.0)
+            .then_sort_by(|v| v.1)
+            .into_sorted_vec();
+        let key = |v: &(i32, &'static str)| (std::cmp::Reverse(v.0), v.1);
+
+        let next = sorted.skip_after_key(&key(&(21, "c")), key);
+        assert_eq!(next, &[(18, "a"), (18, "b")]);
+
+        let next = sorted.skip_after_key(&key(&(18, "a")), key);
+        assert_eq!(next, &[(18, "b")]);
+    }
+
+    #[test]
+    fn merges_two_sorted_vecs() {
+        let left = vec![(1, 'l'), (4, 'l')]
+            .into_iter()
+            .sort_by(|v| v.0)
+            .into_sorted_vec();
+        let right = vec![(4, 'r'), (0, 'r'), (2, 'r')]
+            .into_iter()
+            .sort_by(|v| v.0)
+            .into_sorted_vec();
+
+        let merged: Vec<_> = left.merge(right).unwrap().into();
+
+        assert_eq!(
+            merged,
+            vec![(0, 'r'), (1, 'l'), (2, 'r'), (4, 'l'), (4, 'r')]
+        );
+    }
+
+    #[test]
+    fn merges_many_sorted_vecs() {
+        let shards: Vec<_> = vec![vec![7, 1], vec![5, 3], vec![], vec![2, 8, 4]]
+            .into_iter()
+            .map(|shard| shard.into_iter().sort_by(|v| *v).into_sorted_vec())
+            .collect();
+        let first = vec![6].into_iter().sort_by(|v| *v).into_sorted_vec();
+
+        let merged = first.merge_all(shards).unwrap();
+
+        assert_eq!(merged.as_slice(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn rejects_vecs_in_a_different_order() {
+        let asc = vec![1, 2].into_iter().sort_by(|v| *v).into_sorted_vec();
+        let desc = vec![1, 5, 3]
+            .into_iter()
+            .sort_by_desc(|v| *v)
+            .into_sorted_vec();
+
+        let err = asc.merge(desc).err().unwrap();
+
+        assert_eq!(err, SortError::IncompatibleOrder { input: 0, index: 1 });
+    }
+}
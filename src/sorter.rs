@@ -0,0 +1,336 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{compare_by, compare_by_desc, then_compare_by, then_compare_by_desc, trace, CompareFn};
+
+/// The algorithm a [`Sorter`] uses for each batch.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Strategy {
+    /// Preserves the relative order of equal items.
+    #[default]
+    Stable,
+    /// Faster, but equal items may be reordered.
+    Unstable,
+}
+
+/// Sorts a batch by memoized keys, see [`Sorter::sort_by_memoized_key`].
+trait MemoizedSort<T> {
+    /// Sorts `items` by their cached keys, breaking ties with `then`.
+    fn sort(&self, items: &mut [T], strategy: Strategy, then: &dyn Fn(&T, &T) -> Ordering);
+}
+
+type Memo<'a, T> = Box<dyn MemoizedSort<T> + Send + Sync + 'a>;
+
+/// A sort configuration that is built once and applied to many batches.
+///
+/// The composed comparator is boxed only once, and `sort_iter` collects into
+/// a scratch buffer that is kept around between calls.
+pub struct Sorter<'a, T> {
+    compare: CompareFn<'a, T>,
+    memo: Option<Memo<'a, T>>,
+    strategy: Strategy,
+    buffer: Vec<T>,
+}
+
+impl<'a, T: 'a> Sorter<'a, T> {
+    pub fn sort_by<F, V>(f: F) -> Sorter<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        Sorter {
+            compare: compare_by(f),
+            memo: None,
+            strategy: Strategy::default(),
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn sort_by_desc<F, V>(f: F) -> Sorter<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        Sorter {
+            compare: compare_by_desc(f),
+            memo: None,
+            strategy: Strategy::default(),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Sorts by `key`, computing it only once per item ID `id` returns. The
+    /// keys are cached for the lifetime of the sorter, so repeated sorts of
+    /// the same items reuse them; the key for an ID must not change.
+    ///
+    /// The keys of a batch are looked up once before it is sorted, so the
+    /// comparisons themselves never touch the cache. The cache keeps one key
+    /// per distinct ID and is never evicted, so build a new sorter when the
+    /// IDs are unbounded, e.g. per request.
+    #[cfg(feature = "std")]
+    pub fn sort_by_memoized_key<K, V, N, G>(id: N, key: G) -> Sorter<'a, T>
+    where
+        K: Eq + std::hash::Hash + Send + 'a,
+        V: PartialOrd + Send + 'a,
+        N: Fn(&T) -> K + Send + Sync + 'a,
+        G: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        Sorter {
+            compare: std::sync::Arc::new(|_: &T, _: &T| Ordering::Equal),
+            memo: Some(Box::new(MemoizedKey {
+                id,
+                key,
+                cache: std::sync::Mutex::new(KeyCache {
+                    slots: std::collections::HashMap::new(),
+                    keys: Vec::new(),
+                    batch: Vec::new(),
+                    order: Vec::new(),
+                }),
+            })),
+            strategy: Strategy::default(),
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn then_sort_by<F, V>(self, f: F) -> Sorter<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        Sorter {
+            compare: then_compare_by(self.compare, f),
+            memo: self.memo,
+            strategy: self.strategy,
+            buffer: self.buffer,
+        }
+    }
+
+    pub fn then_sort_by_desc<F, V>(self, f: F) -> Sorter<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        Sorter {
+            compare: then_compare_by_desc(self.compare, f),
+            memo: self.memo,
+            strategy: self.strategy,
+            buffer: self.buffer,
+        }
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Sorter<'a, T> {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Shorthand for `strategy(Strategy::Unstable)`.
+    pub fn unstable(self) -> Sorter<'a, T> {
+        self.strategy(Strategy::Unstable)
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.strategy == Strategy::Stable
+    }
+
+    /// Sorts `vec` in place and hands it back.
+    pub fn sort_vec(&self, mut vec: Vec<T>) -> Vec<T> {
+        self.sort_buffer(&mut vec);
+        vec
+    }
+
+    /// Collects `iter` into the internal scratch buffer, sorts it and drains
+    /// it. The buffer's allocation is reused by the next call.
+    pub fn sort_iter<I>(&mut self, iter: I) -> alloc::vec::Drain<'_, T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut buffer = core::mem::take(&mut self.buffer);
+        buffer.clear();
+        buffer.extend(iter);
+        self.sort_buffer(&mut buffer);
+        self.buffer = buffer;
+        self.buffer.drain(..)
+    }
+
+    fn sort_buffer(&self, items: &mut [T]) {
+        let _span = trace::sort(items);
+        match self.memo {
+            Some(ref memo) => memo.sort(items, self.strategy, &*self.compare),
+            None => sort_slice(items, self.strategy, |a, b| (self.compare)(a, b)),
+        }
+    }
+}
+
+/// The keys cached by [`Sorter::sort_by_memoized_key`], plus scratch buffers
+/// that are reused by every batch.
+#[cfg(feature = "std")]
+struct KeyCache<K, V> {
+    /// Maps every ID to its slot in `keys`.
+    slots: std::collections::HashMap<K, usize>,
+    keys: Vec<V>,
+    /// The slot of every item of the current batch.
+    batch: Vec<usize>,
+    /// The input positions of the current batch in sorted order.
+    order: Vec<usize>,
+}
+
+#[cfg(feature = "std")]
+struct MemoizedKey<K, V, N, G> {
+    id: N,
+    key: G,
+    cache: std::sync::Mutex<KeyCache<K, V>>,
+}
+
+#[cfg(feature = "std")]
+impl<T, K, V, N, G> MemoizedSort<T> for MemoizedKey<K, V, N, G>
+where
+    K: Eq + std::hash::Hash,
+    V: PartialOrd,
+    N: Fn(&T) -> K,
+    G: Fn(&T) -> V,
+{
+    fn sort(&self, items: &mut [T], strategy: Strategy, then: &dyn Fn(&T, &T) -> Ordering) {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let KeyCache {
+            slots,
+            keys,
+            batch,
+            order,
+        } = &mut *cache;
+
+        batch.clear();
+        batch.extend(items.iter().map(|item| {
+            *slots.entry((self.id)(item)).or_insert_with(|| {
+                keys.push((self.key)(item));
+                keys.len() - 1
+            })
+        }));
+        order.clear();
+        order.extend(0..items.len());
+        sort_slice(order, strategy, |&a, &b| {
+            keys[batch[a]]
+                .partial_cmp(&keys[batch[b]])
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| then(&items[a], &items[b]))
+        });
+        permute(items, order);
+    }
+}
+
+/// Moves the item at `order[i]` to position `i`, following each cycle of the
+/// permutation with swaps. Leaves `order` as the identity.
+#[cfg(feature = "std")]
+fn permute<T>(items: &mut [T], order: &mut [usize]) {
+    for start in 0..order.len() {
+        let mut to = start;
+        loop {
+            let from = core::mem::replace(&mut order[to], to);
+            if from == start {
+                break;
+            }
+            items.swap(to, from);
+            to = from;
+        }
+    }
+}
+
+/// Sorts `vec` with `strategy`. With `newest_first`, equal items end up in
+/// reverse input order: the stable sort gets the input reversed, the
+/// unstable sort breaks ties by input position explicitly.
+pub(crate) fn sort_items<T, F>(vec: &mut Vec<T>, strategy: Strategy, newest_first: bool, compare: F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    match (strategy, newest_first) {
+        (_, false) => sort_slice(vec, strategy, compare),
+        (Strategy::Stable, true) => {
+            vec.reverse();
+            vec.sort_by(compare);
+        }
+        (Strategy::Unstable, true) => {
+            let mut indexed: Vec<_> = vec.drain(..).enumerate().collect();
+            indexed.sort_unstable_by(|a, b| compare(&a.1, &b.1).then(b.0.cmp(&a.0)));
+            vec.extend(indexed.into_iter().map(|(_, item)| item));
+        }
+    }
+}
+
+fn sort_slice<T, F>(slice: &mut [T], strategy: Strategy, compare: F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    match strategy {
+        Strategy::Stable => slice.sort_by(compare),
+        Strategy::Unstable => slice.sort_unstable_by(compare),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_many_batches_with_one_sorter() {
+        let mut sorter = Sorter::sort_by(|v: &(i32, i32)| v.0).then_sort_by_desc(|v| v.1);
+
+        let first: Vec<_> = sorter.sort_iter(vec![(2, 1), (1, 1), (2, 3)]).collect();
+        assert_eq!(first, vec![(1, 1), (2, 3), (2, 1)]);
+
+        let second: Vec<_> = sorter.sort_iter(vec![(0, 0), (-1, 5)]).collect();
+        assert_eq!(second, vec![(-1, 5), (0, 0)]);
+        assert!(sorter.buffer.capacity() >= 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn reuses_memoized_keys_across_sorts() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let computed = AtomicUsize::new(0);
+        let mut sorter = Sorter::sort_by_memoized_key(
+            |v: &(u32, &str)| v.0,
+            |v| {
+                computed.fetch_add(1, Ordering::Relaxed);
+                v.1.len()
+            },
+        )
+        .then_sort_by(|v| v.0);
+
+        let first: Vec<_> = sorter
+            .sort_iter(vec![(1, "ccc"), (2, "a"), (3, "bb")])
+            .collect();
+        assert_eq!(first, vec![(2, "a"), (3, "bb"), (1, "ccc")]);
+        assert_eq!(computed.load(Ordering::Relaxed), 3);
+
+        let second: Vec<_> = sorter
+            .sort_iter(vec![(3, "bb"), (1, "ccc"), (4, "")])
+            .collect();
+        assert_eq!(second, vec![(4, ""), (3, "bb"), (1, "ccc")]);
+        assert_eq!(computed.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn sorts_memoized_keys_like_plain_keys() {
+        let input: Vec<(u32, u32)> = (0..50).map(|i| (i * 7 % 50, i * 13 % 5)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|v| (v.1, v.0));
+
+        for strategy in [Strategy::Stable, Strategy::Unstable] {
+            let mut sorter = Sorter::sort_by_memoized_key(|v: &(u32, u32)| v.0, |v| v.1)
+                .then_sort_by(|v| v.0)
+                .strategy(strategy);
+            for _ in 0..2 {
+                let actual: Vec<_> = sorter.sort_iter(input.iter().copied()).collect();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn sorts_vec_in_place() {
+        let sorter = Sorter::sort_by_desc(|v: &i32| *v).strategy(Strategy::Unstable);
+        assert_eq!(sorter.sort_vec(vec![1, 3, 2]), vec![3, 2, 1]);
+    }
+}
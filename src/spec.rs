@@ -0,0 +1,293 @@
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::str::FromStr;
+
+use crate::{CompareFn, Direction};
+
+/// A sort order chosen at runtime, e.g. from a `?sort=-age,name` query
+/// parameter: comma separated field names, most significant first, each
+/// optionally prefixed with `-` for descending or `+` for ascending order.
+///
+/// ```
+/// use sortby::*;
+///
+/// let spec: SortSpec = "-age,name".parse().unwrap();
+/// let compare = spec
+///     .compare_fn(|field| match field {
+///         "age" => Some(std::sync::Arc::new(|a: &(u32, &str), b: &(u32, &str)| a.0.cmp(&b.0))),
+///         "name" => Some(std::sync::Arc::new(|a: &(u32, &str), b: &(u32, &str)| a.1.cmp(b.1))),
+///         _ => None,
+///     })
+///     .unwrap();
+///
+/// let mut people = vec![(30, "Jo"), (40, "Bob"), (30, "Ann")];
+/// people.sort_by(|a, b| compare(a, b));
+/// assert_eq!(people, vec![(40, "Bob"), (30, "Ann"), (30, "Jo")]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SortSpec {
+    pub keys: Vec<SortKey>,
+}
+
+/// One field of a [`SortSpec`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SortKey {
+    pub field: String,
+    pub direction: Direction,
+}
+
+/// The fields a [`SortSpec`] may sort by, and in which directions, see
+/// [`SortSpec::validate`].
+#[derive(Clone, Debug, Default)]
+pub struct AllowedFields {
+    fields: Vec<(String, Option<Direction>)>,
+}
+
+impl AllowedFields {
+    pub fn new() -> AllowedFields {
+        AllowedFields::default()
+    }
+
+    /// Allows sorting by `field` in both directions.
+    pub fn allow(mut self, field: &str) -> AllowedFields {
+        self.fields.push((field.to_string(), None));
+        self
+    }
+
+    /// Allows sorting by `field` in `direction` only, e.g. ascending for a
+    /// field only indexed that way.
+    pub fn allow_only(mut self, field: &str, direction: Direction) -> AllowedFields {
+        self.fields.push((field.to_string(), Some(direction)));
+        self
+    }
+
+    fn check(&self, key: &SortKey) -> Result<(), ForbiddenSortKey> {
+        match self.fields.iter().find(|(field, _)| *field == key.field) {
+            None => Err(ForbiddenSortKey::UnknownField(UnknownField {
+                field: key.field.clone(),
+            })),
+            Some((_, Some(direction))) if *direction != key.direction => {
+                Err(ForbiddenSortKey::Direction(key.clone()))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+impl SortSpec {
+    /// Checks every key against `allowed` before a user-controlled spec is
+    /// applied, failing on the first key that is not allowed.
+    pub fn validate(&self, allowed: &AllowedFields) -> Result<(), ForbiddenSortKey> {
+        self.keys.iter().try_for_each(|key| allowed.check(key))
+    }
+
+    /// Builds the comparator for the spec. `field` returns the ascending
+    /// comparator for a field name, or `None` for names that cannot be
+    /// sorted by, which fails with [`UnknownField`].
+    pub fn compare_fn<'a, T, F>(&self, mut field: F) -> Result<CompareFn<'a, T>, UnknownField>
+    where
+        T: 'a,
+        F: FnMut(&str) -> Option<CompareFn<'a, T>>,
+    {
+        let levels = self
+            .keys
+            .iter()
+            .map(|key| match field(&key.field) {
+                Some(compare) => Ok((compare, key.direction)),
+                None => Err(UnknownField {
+                    field: key.field.clone(),
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Arc::new(move |a, b| {
+            for (compare, direction) in &levels {
+                let ordering = match direction {
+                    Direction::Ascending => compare(a, b),
+                    Direction::Descending => compare(b, a),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
+        }))
+    }
+}
+
+impl FromStr for SortSpec {
+    type Err = InvalidSortSpec;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(SortSpec::default());
+        }
+        let keys = s
+            .split(',')
+            .enumerate()
+            .map(|(index, key)| {
+                let key = key.trim();
+                let (field, direction) = match key.strip_prefix('-') {
+                    Some(field) => (field, Direction::Descending),
+                    None => (key.strip_prefix('+').unwrap_or(key), Direction::Ascending),
+                };
+                if field.is_empty() {
+                    return Err(InvalidSortSpec { index });
+                }
+                Ok(SortKey {
+                    field: field.to_string(),
+                    direction,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(SortSpec { keys })
+    }
+}
+
+impl core::fmt::Display for SortSpec {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, key) in self.keys.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            if key.direction == Direction::Descending {
+                write!(f, "-")?;
+            }
+            write!(f, "{}", key.field)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returned when parsing a [`SortSpec`] finds a key without a field name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidSortSpec {
+    /// Zero-based position of the empty key in the comma separated list.
+    pub index: usize,
+}
+
+impl core::fmt::Display for InvalidSortSpec {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "sort key #{} has no field name", self.index + 1)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidSortSpec {}
+
+/// Returned by [`SortSpec::compare_fn`] for a field that cannot be sorted by.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownField {
+    pub field: String,
+}
+
+impl core::fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "cannot sort by unknown field `{}`", self.field)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownField {}
+
+/// Returned by [`SortSpec::validate`] for a key that is not allowed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ForbiddenSortKey {
+    /// The field is not allowed at all.
+    UnknownField(UnknownField),
+    /// The field is allowed, but not in the key's direction.
+    Direction(SortKey),
+}
+
+impl core::fmt::Display for ForbiddenSortKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ForbiddenSortKey::UnknownField(err) => err.fmt(f),
+            ForbiddenSortKey::Direction(key) => {
+                let direction = match key.direction {
+                    Direction::Ascending => "ascending",
+                    Direction::Descending => "descending",
+                };
+                write!(f, "cannot sort by `{}` {}", key.field, direction)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ForbiddenSortKey {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_keys() {
+        let spec: SortSpec = "-age, +name,id".parse().unwrap();
+
+        assert_eq!(
+            spec.keys,
+            vec![
+                SortKey {
+                    field: "age".to_string(),
+                    direction: Direction::Descending
+                },
+                SortKey {
+                    field: "name".to_string(),
+                    direction: Direction::Ascending
+                },
+                SortKey {
+                    field: "id".to_string(),
+                    direction: Direction::Ascending
+                },
+            ]
+        );
+        assert_eq!(spec.to_string(), "-age,name,id");
+        assert_eq!("".parse::<SortSpec>(), Ok(SortSpec::default()));
+        assert_eq!(
+            "age,-".parse::<SortSpec>(),
+            Err(InvalidSortSpec { index: 1 })
+        );
+    }
+
+    #[test]
+    fn validates_against_allowed_fields() {
+        let allowed = AllowedFields::new()
+            .allow("age")
+            .allow_only("name", Direction::Ascending);
+
+        let spec: SortSpec = "-age,name".parse().unwrap();
+        assert_eq!(spec.validate(&allowed), Ok(()));
+
+        let spec: SortSpec = "age,password".parse().unwrap();
+        assert_eq!(
+            spec.validate(&allowed),
+            Err(ForbiddenSortKey::UnknownField(UnknownField {
+                field: "password".to_string()
+            }))
+        );
+
+        let spec: SortSpec = "-name".parse().unwrap();
+        let err = spec.validate(&allowed).unwrap_err();
+        assert_eq!(err.to_string(), "cannot sort by `name` descending");
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let spec: SortSpec = "age,salary".parse().unwrap();
+
+        let actual = spec.compare_fn(|field| match field {
+            "age" => Some(Arc::new(|a: &u32, b: &u32| a.cmp(b)) as CompareFn<'_, u32>),
+            _ => None,
+        });
+
+        assert_eq!(
+            actual.err(),
+            Some(UnknownField {
+                field: "salary".to_string()
+            })
+        );
+    }
+}
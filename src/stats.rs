@@ -0,0 +1,104 @@
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::sync::atomic::{AtomicU64, Ordering as Atomic};
+
+use crate::Compare;
+
+/// Counters recorded while a [`SortBy`](crate::SortBy) sorts, see
+/// [`SortBy::stats`](crate::SortBy::stats). Requires the `stats` feature.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SortStats {
+    /// Number of times two items were compared.
+    pub comparisons: u64,
+    /// Number of keys extracted from items. Boxed and mapped comparators are
+    /// opaque, so the keys they extract are not counted.
+    pub key_extractions: u64,
+}
+
+pub(crate) fn count_keys(stats: &Cell<SortStats>, n: u64) {
+    let mut counts = stats.get();
+    counts.key_extractions += n;
+    stats.set(counts);
+}
+
+/// The counters a sort keeps. They are atomic so that a sort stays `Sync`,
+/// e.g. to be shared with another thread before it is iterated.
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    comparisons: AtomicU64,
+    key_extractions: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn get(&self) -> SortStats {
+        SortStats {
+            comparisons: self.comparisons.load(Atomic::Relaxed),
+            key_extractions: self.key_extractions.load(Atomic::Relaxed),
+        }
+    }
+
+    fn record(&self, counts: SortStats) {
+        self.comparisons
+            .fetch_add(counts.comparisons, Atomic::Relaxed);
+        self.key_extractions
+            .fetch_add(counts.key_extractions, Atomic::Relaxed);
+    }
+}
+
+impl Clone for Counters {
+    fn clone(&self) -> Counters {
+        let counters = Counters::default();
+        counters.record(self.get());
+        counters
+    }
+}
+
+/// Counts every comparison made through the wrapped comparator.
+pub(crate) struct Counted<'s, C> {
+    pub(crate) compare: &'s C,
+    pub(crate) stats: &'s Counters,
+}
+
+impl<'s, T, C> Compare<T> for Counted<'s, C>
+where
+    C: Compare<T>,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        let keys = Cell::new(SortStats::default());
+        let ordering = self.compare.compare_counting(a, b, &keys);
+        self.stats.record(SortStats {
+            comparisons: 1,
+            key_extractions: keys.get().key_extractions,
+        });
+        ordering
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn counts_comparisons_and_keys() {
+        let mut sort = vec![(2, 'a'), (1, 'b'), (1, 'a')]
+            .into_iter()
+            .sort_by(|v| v.0)
+            .then_sort_by(|v| v.1);
+        assert_eq!(sort.stats(), SortStats::default());
+
+        assert_eq!(sort.next(), Some((1, 'a')));
+        let stats = sort.stats();
+        assert!(stats.comparisons >= 2);
+        // every comparison extracts both first-level keys, ties also the second
+        assert!(stats.key_extractions >= 2 * stats.comparisons + 2);
+    }
+
+    #[test]
+    fn keeps_sorts_sync() {
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let sort = vec![2, 1].into_iter().sort_by(|v| *v).boxed();
+        assert_sync(&sort);
+    }
+}
@@ -0,0 +1,198 @@
+//! Instrumentation around the phases of a sort. With the `tracing` feature
+//! each phase is a span; keys are extracted lazily while comparing, so that
+//! work is part of the `sortby::sort` span. With the `metrics` feature every
+//! phase reports its duration, item count and buffer size in bytes, and the
+//! `sortby.memory_high_water_bytes` gauge tracks the most bytes the buffers
+//! of all sorts in flight held at once. None of the sorts spill to disk, so
+//! there are no spill metrics. Without either feature all of this compiles
+//! to nothing, except for notifying the global
+//! [`SortObserver`](crate::SortObserver), if one is installed.
+
+// arguments and type parameters are only used by some of the features
+#![allow(unused_variables, clippy::extra_unused_type_parameters)]
+
+#[cfg(feature = "metrics")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+use crate::observer::{self, SortObserver};
+
+/// Ends the phase when dropped.
+pub(crate) struct Span<'o> {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "metrics")]
+    timer: Option<Timer>,
+    phase: Phase,
+    items: usize,
+    /// The observer of the sort this phase belongs to, see
+    /// [`observed_by`](Span::observed_by).
+    local: Option<&'o (dyn SortObserver + Sync)>,
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    Collect,
+    Sort,
+    Merge { runs: usize },
+}
+
+impl<'o> Drop for Span<'o> {
+    fn drop(&mut self) {
+        let (phase, items) = (self.phase, self.items);
+        let end = |observer: &dyn SortObserver| match phase {
+            Phase::Collect => observer.on_collect_end(items),
+            Phase::Sort => observer.on_sort_end(items),
+            Phase::Merge { runs } => observer.on_merge(runs, items),
+        };
+        if let Some(local) = self.local {
+            end(local);
+        }
+        observer::notify_global(end);
+    }
+}
+
+/// The bytes held by the buffers of all phases in flight.
+#[cfg(feature = "metrics")]
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+/// The most bytes [`LIVE_BYTES`] ever reached.
+#[cfg(feature = "metrics")]
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "metrics")]
+struct Timer {
+    phase: &'static str,
+    start: Instant,
+    items: usize,
+    item_size: usize,
+    /// The bytes this phase added to [`LIVE_BYTES`].
+    live: usize,
+}
+
+#[cfg(feature = "metrics")]
+impl Timer {
+    fn new<T>(phase: &'static str, items: usize) -> Timer {
+        let mut timer = Timer {
+            phase,
+            start: Instant::now(),
+            items: 0,
+            item_size: core::mem::size_of::<T>(),
+            live: 0,
+        };
+        timer.set_items(items);
+        timer
+    }
+
+    /// Records the buffer size and raises the high-water mark if needed.
+    fn set_items(&mut self, items: usize) {
+        self.items = items;
+        let bytes = items.saturating_mul(self.item_size);
+        if bytes > self.live {
+            let added = bytes - self.live;
+            let live = LIVE_BYTES.fetch_add(added, Ordering::Relaxed) + added;
+            let peak = PEAK_BYTES.fetch_max(live, Ordering::Relaxed).max(live);
+            metrics::gauge!("sortby.memory_high_water_bytes").set(peak as f64);
+        } else {
+            LIVE_BYTES.fetch_sub(self.live - bytes, Ordering::Relaxed);
+        }
+        self.live = bytes;
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for Timer {
+    fn drop(&mut self) {
+        LIVE_BYTES.fetch_sub(self.live, Ordering::Relaxed);
+        let phase = self.phase;
+        metrics::histogram!("sortby.duration_seconds", "phase" => phase)
+            .record(self.start.elapsed().as_secs_f64());
+        metrics::histogram!("sortby.items", "phase" => phase).record(self.items as f64);
+        metrics::histogram!("sortby.buffer_bytes", "phase" => phase)
+            .record((self.items * self.item_size) as f64);
+    }
+}
+
+impl<'o> Span<'o> {
+    /// Reports the phase to the observer of a single sort as well, if it has
+    /// one.
+    pub(crate) fn observed_by(mut self, local: Option<&'o (dyn SortObserver + Sync)>) -> Span<'o> {
+        if let Some(local) = local {
+            match self.phase {
+                Phase::Collect => local.on_collect_start(),
+                Phase::Sort => local.on_sort_start(self.items),
+                Phase::Merge { .. } => {}
+            }
+        }
+        self.local = local;
+        self
+    }
+
+    pub(crate) fn record_items(&mut self, items: usize) {
+        self.items = items;
+        #[cfg(feature = "tracing")]
+        self.span.record("items", items);
+        #[cfg(feature = "metrics")]
+        if let Some(timer) = &mut self.timer {
+            timer.set_items(items);
+        }
+    }
+}
+
+/// Collecting the source iterator into a buffer of `T`; `items` is recorded
+/// once it is drained.
+pub(crate) fn collect<'o, T>() -> Span<'o> {
+    observer::notify_global(|observer| observer.on_collect_start());
+    Span {
+        #[cfg(feature = "tracing")]
+        span: tracing::debug_span!("sortby::collect", items = tracing::field::Empty).entered(),
+        #[cfg(feature = "metrics")]
+        timer: Some(Timer::new::<T>("collect", 0)),
+        phase: Phase::Collect,
+        items: 0,
+        local: None,
+    }
+}
+
+pub(crate) fn sort<'o, T>(items: &[T]) -> Span<'o> {
+    observer::notify_global(|observer| observer.on_sort_start(items.len()));
+    Span {
+        #[cfg(feature = "tracing")]
+        span: tracing::debug_span!("sortby::sort", items = items.len()).entered(),
+        #[cfg(feature = "metrics")]
+        timer: Some(Timer::new::<T>("sort", items.len())),
+        phase: Phase::Sort,
+        items: items.len(),
+        local: None,
+    }
+}
+
+/// Merging `runs` sorted runs of `T`; `items` is recorded separately.
+pub(crate) fn merge<'o, T>(runs: usize) -> Span<'o> {
+    Span {
+        #[cfg(feature = "tracing")]
+        span: tracing::debug_span!("sortby::merge", runs, items = tracing::field::Empty).entered(),
+        #[cfg(feature = "metrics")]
+        timer: Some(Timer::new::<T>("merge", 0)),
+        phase: Phase::Merge { runs },
+        items: 0,
+        local: None,
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "metrics")]
+mod tests {
+    use core::sync::atomic::Ordering;
+
+    use super::PEAK_BYTES;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn tracks_the_high_water_mark_of_sort_buffers() {
+        let sorted: Vec<u64> = (0..1000u64).rev().sort_by(|v| *v).collect();
+
+        assert_eq!(sorted.len(), 1000);
+        assert!(PEAK_BYTES.load(Ordering::Relaxed) >= 8000);
+    }
+}
@@ -0,0 +1,175 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{
+    compare_by, compare_by_desc, then_compare_by, then_compare_by_desc, CompareFn, Strategy,
+};
+
+/// A chained sort that owns a `Vec`. Call [`sort`] (or convert into a `Vec`)
+/// to sort it in place and get it back.
+///
+/// [`sort`]: VecSortBy::sort
+pub struct VecSortBy<'a, T> {
+    vec: Vec<T>,
+    compare: CompareFn<'a, T>,
+    strategy: Strategy,
+}
+
+impl<'a, T: 'a> VecSortBy<'a, T> {
+    pub fn then_sort_by<F, V>(self, f: F) -> VecSortBy<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        VecSortBy {
+            vec: self.vec,
+            compare: then_compare_by(self.compare, f),
+            strategy: self.strategy,
+        }
+    }
+
+    pub fn then_sort_by_desc<F, V>(self, f: F) -> VecSortBy<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        VecSortBy {
+            vec: self.vec,
+            compare: then_compare_by_desc(self.compare, f),
+            strategy: self.strategy,
+        }
+    }
+
+    /// Gives up the stable order of equal items for a faster sort.
+    pub fn unstable(mut self) -> VecSortBy<'a, T> {
+        self.strategy = Strategy::Unstable;
+        self
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.strategy == Strategy::Stable
+    }
+
+    /// Sorts the `Vec` in place, stable unless [`unstable`] was called, and
+    /// returns it.
+    ///
+    /// [`unstable`]: VecSortBy::unstable
+    pub fn sort(mut self) -> Vec<T> {
+        let compare = self.compare;
+        match self.strategy {
+            Strategy::Stable => self.vec.sort_by(|a, b| compare(a, b)),
+            Strategy::Unstable => self.vec.sort_unstable_by(|a, b| compare(a, b)),
+        }
+        self.vec
+    }
+}
+
+impl<'a, T: 'a> From<VecSortBy<'a, T>> for Vec<T> {
+    fn from(val: VecSortBy<'a, T>) -> Self {
+        val.sort()
+    }
+}
+
+/// Chained in-place sorting for an owned `Vec`.
+///
+/// The methods share their names with [`SliceSortByExt`](crate::SliceSortByExt)
+/// rather than being called `sort_by`, which would shadow `<[T]>::sort_by`
+/// for every `Vec` once this trait is in scope.
+pub trait VecSortByExt<T> {
+    fn sort_by_key_chain<'a, F, V>(self, f: F) -> VecSortBy<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a;
+
+    fn sort_by_key_chain_desc<'a, F, V>(self, f: F) -> VecSortBy<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a;
+}
+
+impl<T> VecSortByExt<T> for Vec<T> {
+    fn sort_by_key_chain<'a, F, V>(self, f: F) -> VecSortBy<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        VecSortBy {
+            vec: self,
+            compare: compare_by(f),
+            strategy: Strategy::default(),
+        }
+    }
+
+    fn sort_by_key_chain_desc<'a, F, V>(self, f: F) -> VecSortBy<'a, T>
+    where
+        V: PartialOrd,
+        F: Fn(&T) -> V + Send + Sync + 'a,
+    {
+        VecSortBy {
+            vec: self,
+            compare: compare_by_desc(f),
+            strategy: Strategy::default(),
+        }
+    }
+}
+
+/// Inserts `item` into `vec`, which must be sorted by `compare`, behind all
+/// items that compare equal to it, and returns its index. Finding the
+/// position is a binary search.
+///
+/// Pass the comparator of the sort that produced `vec`, e.g. from
+/// [`SortBy::compare_fn`](crate::SortBy::compare_fn), so newcomers land
+/// where the original sort would have put them.
+///
+/// ```
+/// use sortby::*;
+///
+/// let sort = vec![(2, "b"), (1, "a")].into_iter().sort_by(|v| v.0).then_sort_by(|v| v.1);
+/// let compare = sort.compare_fn();
+/// let mut sorted: Vec<_> = sort.clone().collect();
+///
+/// assert_eq!(insert_sorted_by(&mut sorted, (1, "c"), &compare), 1);
+/// assert_eq!(sorted, vec![(1, "a"), (1, "c"), (2, "b")]);
+/// ```
+pub fn insert_sorted_by<T, F>(vec: &mut Vec<T>, item: T, mut compare: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let index = vec.partition_point(|probe| compare(probe, &item) != Ordering::Greater);
+    vec.insert(index, item);
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_behind_equal_items() {
+        let mut sorted = vec![(1, 'a'), (2, 'b'), (2, 'c'), (3, 'd')];
+
+        let index = insert_sorted_by(&mut sorted, (2, 'x'), |a, b| a.0.cmp(&b.0));
+        assert_eq!(index, 3);
+        assert_eq!(
+            sorted,
+            vec![(1, 'a'), (2, 'b'), (2, 'c'), (2, 'x'), (3, 'd')]
+        );
+
+        let index = insert_sorted_by(&mut sorted, (0, 'y'), |a, b| a.0.cmp(&b.0));
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn sorts_vec_in_place_and_returns_it() {
+        let data = vec![(1, "b"), (0, "z"), (1, "a"), (0, "y")];
+        let ptr = data.as_ptr();
+
+        let sorted = data
+            .sort_by_key_chain(|v| v.0)
+            .then_sort_by_desc(|v| v.1)
+            .sort();
+
+        assert_eq!(sorted, vec![(0, "z"), (0, "y"), (1, "b"), (1, "a")]);
+        assert_eq!(sorted.as_ptr(), ptr);
+    }
+}
@@ -0,0 +1,334 @@
+use alloc::boxed::Box;
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::vec::Vec;
+use core::ops::Add;
+
+use crate::reorder::Entry;
+
+/// What a [`WatermarkSorter`] does with items that arrive after something
+/// newer has already been released.
+#[derive(Default)]
+pub enum LatePolicy<'a, T> {
+    /// Discard the item; it is still counted in
+    /// [`dropped`](WatermarkSorter::dropped).
+    #[default]
+    Drop,
+    /// Release the item right away, out of order and flagged as
+    /// [`Released::Late`].
+    Emit,
+    /// Hand the item to a callback, e.g. one that sends it to a channel.
+    Callback(Box<dyn FnMut(T) + 'a>),
+}
+
+/// An item released by a [`WatermarkSorter`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Released<T> {
+    /// Released in event-time order.
+    InOrder(T),
+    /// Arrived too late and is out of order, see [`LatePolicy::Emit`].
+    Late(T),
+}
+
+impl<T> Released<T> {
+    pub fn is_late(&self) -> bool {
+        matches!(self, Released::Late(_))
+    }
+
+    pub fn into_inner(self) -> T {
+        match self {
+            Released::InOrder(item) | Released::Late(item) => item,
+        }
+    }
+}
+
+/// Orders a stream of timestamped items by event time.
+///
+/// Items are buffered until the newest timestamp seen so far is at least the
+/// allowed lateness ahead of them; only then are they released, oldest first.
+/// An item that is older than something already released is late and gets
+/// handled according to the [`LatePolicy`], dropping it by default.
+///
+/// Timestamps can be anything that `lateness` can be added to, e.g.
+/// `SystemTime` or `Instant` with a `Duration`, or plain integers.
+pub struct WatermarkSorter<'a, T, K, D, F> {
+    key: F,
+    lateness: D,
+    policy: LatePolicy<'a, T>,
+    heap: BinaryHeap<Entry<K, T>>,
+    late: VecDeque<T>,
+    seq: usize,
+    newest: Option<K>,
+    released: Option<K>,
+    late_count: usize,
+    dropped: usize,
+}
+
+impl<'a, T, K, D, F> WatermarkSorter<'a, T, K, D, F>
+where
+    K: PartialOrd + Clone + Add<D, Output = K>,
+    D: Clone,
+    F: Fn(&T) -> K,
+{
+    pub fn new(lateness: D, key: F) -> WatermarkSorter<'a, T, K, D, F> {
+        WatermarkSorter {
+            key,
+            lateness,
+            policy: LatePolicy::default(),
+            heap: BinaryHeap::new(),
+            late: VecDeque::new(),
+            seq: 0,
+            newest: None,
+            released: None,
+            late_count: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn late_policy(mut self, policy: LatePolicy<'a, T>) -> WatermarkSorter<'a, T, K, D, F> {
+        self.policy = policy;
+        self
+    }
+
+    /// Buffers `item`. Returns `false` if it was late, in which case it was
+    /// handled by the [`LatePolicy`] instead.
+    pub fn push(&mut self, item: T) -> bool {
+        let key = (self.key)(&item);
+        if self.is_late(&key) {
+            self.late_count += 1;
+            match self.policy {
+                LatePolicy::Drop => self.dropped += 1,
+                LatePolicy::Emit => self.late.push_back(item),
+                LatePolicy::Callback(ref mut callback) => callback(item),
+            }
+            return false;
+        }
+
+        if self.newest.as_ref().is_none_or(|newest| key > *newest) {
+            self.newest = Some(key.clone());
+        }
+        self.heap.push(Entry {
+            key,
+            seq: self.seq,
+            item,
+        });
+        self.seq += 1;
+        true
+    }
+
+    /// Releases the oldest buffered item if the watermark has passed it.
+    /// Late items waiting to be emitted come first.
+    pub fn pop_ready(&mut self) -> Option<T> {
+        self.pop_released().map(Released::into_inner)
+    }
+
+    /// Like [`pop_ready`](WatermarkSorter::pop_ready), but tells in-order and
+    /// late items apart.
+    pub fn pop_released(&mut self) -> Option<Released<T>> {
+        if let Some(item) = self.late.pop_front() {
+            return Some(Released::Late(item));
+        }
+
+        let newest = self.newest.as_ref()?;
+        let ready = self
+            .heap
+            .peek()
+            .is_some_and(|next| next.key.clone() + self.lateness.clone() <= *newest);
+        if ready {
+            self.release().map(Released::InOrder)
+        } else {
+            None
+        }
+    }
+
+    /// Releases everything that is still buffered, e.g. at the end of the
+    /// stream, oldest first.
+    pub fn flush(&mut self) -> Vec<T> {
+        let mut items = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop_any() {
+            items.push(item.into_inner());
+        }
+        items
+    }
+
+    /// Number of buffered items, including late items waiting to be emitted.
+    pub fn len(&self) -> usize {
+        self.heap.len() + self.late.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty() && self.late.is_empty()
+    }
+
+    /// Number of late items seen so far, whatever the policy.
+    pub fn late(&self) -> usize {
+        self.late_count
+    }
+
+    /// Number of late items that were dropped so far.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    fn pop_any(&mut self) -> Option<Released<T>> {
+        match self.late.pop_front() {
+            Some(item) => Some(Released::Late(item)),
+            None => self.release().map(Released::InOrder),
+        }
+    }
+
+    fn is_late(&self, key: &K) -> bool {
+        self.released
+            .as_ref()
+            .is_some_and(|released| key < released)
+    }
+
+    fn release(&mut self) -> Option<T> {
+        let entry = self.heap.pop()?;
+        self.released = Some(entry.key);
+        Some(entry.item)
+    }
+}
+
+/// Iterator adaptor around a [`WatermarkSorter`], see
+/// [`SortByIteratorExt::sort_by_watermark`](crate::SortByIteratorExt::sort_by_watermark).
+pub struct WatermarkSorted<'a, I: Iterator, K, D, F> {
+    iter: Option<I>,
+    sorter: WatermarkSorter<'a, I::Item, K, D, F>,
+}
+
+impl<'a, I, K, D, F> WatermarkSorted<'a, I, K, D, F>
+where
+    I: Iterator,
+    K: PartialOrd + Clone + Add<D, Output = K>,
+    D: Clone,
+    F: Fn(&I::Item) -> K,
+{
+    pub(crate) fn new(iter: I, lateness: D, key: F) -> WatermarkSorted<'a, I, K, D, F> {
+        WatermarkSorted {
+            iter: Some(iter),
+            sorter: WatermarkSorter::new(lateness, key),
+        }
+    }
+
+    pub fn late_policy(
+        mut self,
+        policy: LatePolicy<'a, I::Item>,
+    ) -> WatermarkSorted<'a, I, K, D, F> {
+        self.sorter = self.sorter.late_policy(policy);
+        self
+    }
+
+    pub fn sorter(&self) -> &WatermarkSorter<'a, I::Item, K, D, F> {
+        &self.sorter
+    }
+
+    /// Like `next`, but tells in-order and late items apart.
+    pub fn next_released(&mut self) -> Option<Released<I::Item>> {
+        loop {
+            let iter = match self.iter {
+                Some(ref mut iter) => iter,
+                None => return self.sorter.pop_any(),
+            };
+            if let Some(item) = self.sorter.pop_released() {
+                return Some(item);
+            }
+            match iter.next() {
+                Some(item) => {
+                    self.sorter.push(item);
+                }
+                None => self.iter = None,
+            }
+        }
+    }
+}
+
+impl<'a, I, K, D, F> Iterator for WatermarkSorted<'a, I, K, D, F>
+where
+    I: Iterator,
+    K: PartialOrd + Clone + Add<D, Output = K>,
+    D: Clone,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_released().map(Released::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortByIteratorExt;
+
+    #[test]
+    fn releases_items_once_watermark_passes() {
+        let mut sorter = WatermarkSorter::new(10u64, |v: &(u64, char)| v.0);
+
+        sorter.push((100, 'a'));
+        sorter.push((95, 'b'));
+        assert_eq!(sorter.pop_ready(), None);
+
+        sorter.push((106, 'c'));
+        assert_eq!(sorter.pop_ready(), Some((95, 'b')));
+        assert_eq!(sorter.pop_ready(), None);
+
+        assert!(!sorter.push((90, 'd')));
+        assert_eq!(sorter.dropped(), 1);
+
+        assert_eq!(sorter.flush(), vec![(100, 'a'), (106, 'c')]);
+        assert!(sorter.is_empty());
+    }
+
+    #[test]
+    fn sorts_iterator_by_event_time() {
+        let events = vec![3u32, 1, 2, 7, 5, 6, 12, 4, 11, 10];
+
+        let sorted = events.into_iter().sort_by_watermark(3, |v| *v);
+        let actual: Vec<_> = sorted.collect();
+
+        // 4 arrives after 7 was released and is dropped
+        assert_eq!(actual, vec![1, 2, 3, 5, 6, 7, 10, 11, 12]);
+    }
+
+    #[test]
+    fn emits_late_items_flagged() {
+        let mut sorted = vec![1u32, 5, 9, 2]
+            .into_iter()
+            .sort_by_watermark(2, |v| *v)
+            .late_policy(LatePolicy::Emit);
+
+        let mut actual = Vec::new();
+        while let Some(item) = sorted.next_released() {
+            actual.push(item);
+        }
+
+        assert_eq!(
+            actual,
+            vec![
+                Released::InOrder(1),
+                Released::InOrder(5),
+                Released::Late(2),
+                Released::InOrder(9),
+            ]
+        );
+        assert_eq!(sorted.sorter().late(), 1);
+        assert_eq!(sorted.sorter().dropped(), 0);
+    }
+
+    #[test]
+    fn routes_late_items_to_callback() {
+        let mut late = Vec::new();
+        {
+            let mut sorter = WatermarkSorter::new(0i64, |v: &i64| *v)
+                .late_policy(LatePolicy::Callback(Box::new(|item| late.push(item))));
+            sorter.push(4);
+            sorter.push(6);
+            assert_eq!(sorter.pop_ready(), Some(4));
+            assert!(!sorter.push(3));
+            assert_eq!(sorter.flush(), vec![6]);
+        }
+
+        assert_eq!(late, vec![3]);
+    }
+}
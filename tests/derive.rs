@@ -0,0 +1,120 @@
+use sortby::*;
+
+#[derive(DefaultSort, Debug, PartialEq)]
+struct Person {
+    #[sort(by = "len", level = 1)]
+    name: &'static str,
+    #[sort(desc, level = 0)]
+    age: u32,
+    city: &'static str,
+}
+
+fn person(name: &'static str, age: u32, city: &'static str) -> Person {
+    Person { name, age, city }
+}
+
+#[test]
+fn sorts_by_levels_methods_and_direction() {
+    let people = vec![
+        person("Ann", 30, "Oslo"),
+        person("Bob", 40, "Rome"),
+        person("Jo", 30, "Lima"),
+        person("Eve", 30, "Kyiv"),
+    ];
+
+    let actual: Vec<_> = people
+        .into_iter()
+        .sort_default()
+        .map(|p| (p.name, p.city))
+        .collect();
+
+    // unmarked fields are ignored, so Ann and Eve keep their input order
+    assert_eq!(
+        actual,
+        vec![
+            ("Bob", "Rome"),
+            ("Jo", "Lima"),
+            ("Ann", "Oslo"),
+            ("Eve", "Kyiv")
+        ]
+    );
+}
+
+#[derive(DefaultSort)]
+struct Version(#[sort] u32, #[sort(desc)] u32);
+
+#[test]
+fn sorts_tuple_structs_in_field_order() {
+    let actual: Vec<_> = vec![Version(1, 2), Version(0, 5), Version(1, 7)]
+        .into_iter()
+        .sort_default()
+        .map(|v| (v.0, v.1))
+        .collect();
+
+    assert_eq!(actual, vec![(0, 5), (1, 7), (1, 2)]);
+}
+
+#[derive(EnumOrder, Clone, Copy, Debug, PartialEq)]
+enum Ticket {
+    #[order(rank = 1)]
+    #[order(name = "triage", rank = 0)]
+    Open,
+    #[order(rank = 0)]
+    #[order(name = "triage", rank = 1)]
+    Blocked {
+        reason: &'static str,
+    },
+    #[order(name = "archive", rank = 0)]
+    Closed(u32),
+    Draft,
+}
+
+#[test]
+fn orders_enums_by_declared_ranks() {
+    let input = [
+        Ticket::Draft,
+        Ticket::Closed(2),
+        Ticket::Open,
+        Ticket::Blocked { reason: "ci" },
+    ];
+    let sort = |order: &EnumOrder<Ticket>| -> Vec<_> {
+        input
+            .iter()
+            .copied()
+            .sort_by_enum_order(|v| v, order)
+            .collect()
+    };
+
+    // variants without a rank sort last and keep their input order
+    assert_eq!(
+        sort(&EnumOrder::declared()),
+        vec![
+            Ticket::Blocked { reason: "ci" },
+            Ticket::Open,
+            Ticket::Draft,
+            Ticket::Closed(2)
+        ]
+    );
+    assert_eq!(
+        sort(&EnumOrder::named("triage").unwrap()),
+        vec![
+            Ticket::Open,
+            Ticket::Blocked { reason: "ci" },
+            Ticket::Draft,
+            Ticket::Closed(2)
+        ]
+    );
+    assert_eq!(
+        EnumOrder::named("archive")
+            .unwrap()
+            .rank(&Ticket::Closed(7)),
+        0
+    );
+    assert!(EnumOrder::<Ticket>::named("inbox").is_none());
+}
+
+#[test]
+fn rejects_invalid_input() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,35 @@
+use sortby::*;
+
+#[test]
+fn applies_the_global_incomparable_policy_to_plain_sorts() {
+    assert_eq!(
+        SortConfig::new().unstable().install_global(),
+        Err(UnsupportedGlobalOption { option: "strategy" })
+    );
+    assert_eq!(
+        SortConfig::new()
+            .max_items(10, Overflow::Truncate)
+            .install_global(),
+        Err(UnsupportedGlobalOption {
+            option: "max_items"
+        })
+    );
+    assert_eq!(SortConfig::global(), SortConfig::new());
+
+    SortConfig::new()
+        .incomparable(Incomparable::Error)
+        .install_global()
+        .unwrap();
+
+    let input = [(2.0, 'a'), (f64::NAN, 'b'), (1.0, 'c'), (1.0, 'd')];
+    let actual: Vec<_> = input.iter().sort_by(|v| v.0).map(|v| v.1).collect();
+    assert_eq!(actual, vec!['c', 'd', 'a', 'b']);
+
+    let actual = input.iter().sort_by_with(
+        |v| v.0,
+        &SortConfig::new().incomparable(Incomparable::Error),
+    );
+    assert_eq!(actual.err(), Some(SortError::IncomparableKeys { index: 1 }));
+
+    SortConfig::reset_global();
+}
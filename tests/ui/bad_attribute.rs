@@ -0,0 +1,9 @@
+use sortby::DefaultSort;
+
+#[derive(DefaultSort)]
+struct Person {
+    #[sort(ascending)]
+    name: String,
+}
+
+fn main() {}
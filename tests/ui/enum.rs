@@ -0,0 +1,9 @@
+use sortby::DefaultSort;
+
+#[derive(DefaultSort)]
+enum Level {
+    Low,
+    High,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sortby::EnumOrder;
+
+#[derive(EnumOrder)]
+enum Level {
+    #[order(first)]
+    Low,
+    High,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use sortby::EnumOrder;
+
+#[derive(EnumOrder)]
+enum Level {
+    #[order(name = "triage", rank = 0)]
+    #[order(name = "triage", rank = 1)]
+    Low,
+    High,
+}
+
+fn main() {}
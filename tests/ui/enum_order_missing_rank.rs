@@ -0,0 +1,10 @@
+use sortby::EnumOrder;
+
+#[derive(EnumOrder)]
+enum Level {
+    #[order(name = "triage")]
+    Low,
+    High,
+}
+
+fn main() {}
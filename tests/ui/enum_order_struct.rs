@@ -0,0 +1,8 @@
+use sortby::EnumOrder;
+
+#[derive(EnumOrder)]
+struct Level {
+    rank: u32,
+}
+
+fn main() {}
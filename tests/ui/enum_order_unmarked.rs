@@ -0,0 +1,9 @@
+use sortby::EnumOrder;
+
+#[derive(EnumOrder)]
+enum Level {
+    Low,
+    High,
+}
+
+fn main() {}
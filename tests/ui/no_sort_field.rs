@@ -0,0 +1,9 @@
+use sortby::DefaultSort;
+
+#[derive(DefaultSort)]
+struct Person {
+    name: String,
+    age: u32,
+}
+
+fn main() {}